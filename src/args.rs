@@ -0,0 +1,14 @@
+use std::{env, str::FromStr};
+
+// Returns true if the flag was passed on the command line
+pub fn flag(name: &str) -> bool {
+    env::args().any(|arg| arg == name)
+}
+
+// Returns the value following a flag, e.g. `--seed 42`
+pub fn value<T: FromStr>(name: &str) -> Option<T> {
+    env::args()
+        .skip_while(|arg| arg != name)
+        .nth(1)
+        .and_then(|value| value.parse().ok())
+}
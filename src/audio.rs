@@ -1,12 +1,105 @@
 use crate::{
     constants::*,
-    game::{beetle::Beetle, player::Player},
+    game::{beetle::Beetle, demo::DemoMode, player::Player},
     AppState, GameState,
 };
-use bevy::{prelude::*, utils::Duration};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::Duration};
 use bevy_kira_audio::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::game::{millipede::Segment, scorpion::Scorpion, spider::Spider};
+use crate::game::{inchworm::SlowMotion, millipede::Segment, scorpion::Scorpion, spider::Spider};
+
+const AUDIO_SETTINGS_KEY: &str = "audio";
+
+// The player's volume levels, from 0 to 1. Every sound is scaled by these
+#[derive(Resource, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub master: f64,
+    pub music: f64,
+    pub sfx: f64,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            master: 1.0,
+            music: 1.0,
+            sfx: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn load() -> Self {
+        let Some(saved) = crate::storage::load(AUDIO_SETTINGS_KEY) else {
+            return AudioSettings::default();
+        };
+        ron::from_str(&saved).unwrap_or_else(|error| {
+            warn!("Could not read saved audio settings: {}", error);
+            AudioSettings::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let saved = match ron::to_string(self) {
+            Ok(saved) => saved,
+            Err(error) => {
+                warn!("Could not save audio settings: {}", error);
+                return;
+            }
+        };
+        if let Err(error) = crate::storage::save(AUDIO_SETTINGS_KEY, &saved) {
+            warn!("Could not save audio settings: {}", error);
+        }
+    }
+
+    pub fn music_volume(&self, volume: f64) -> f64 {
+        if self.muted {
+            return 0.0;
+        }
+        volume * self.master * self.music
+    }
+
+    pub fn sfx_volume(&self, volume: f64) -> f64 {
+        if self.muted {
+            return 0.0;
+        }
+        volume * self.master * self.sfx
+    }
+
+    // Moves a level up or down by whole steps, so it always shows as a round percentage
+    pub fn step(level: f64, steps: f64) -> f64 {
+        ((level / VOLUME_STEP).round() + steps).clamp(0.0, 1.0 / VOLUME_STEP) * VOLUME_STEP
+    }
+}
+
+// Plays one-off sounds at the player's volume. Does nothing without audio,
+// e.g. when running headless
+#[derive(SystemParam)]
+pub struct SoundEffects<'w> {
+    audio: Option<Res<'w, Audio>>,
+    audio_handles: Option<Res<'w, AudioHandles>>,
+    audio_settings: Option<Res<'w, AudioSettings>>,
+    demo_mode: Option<Res<'w, DemoMode>>,
+}
+
+impl SoundEffects<'_> {
+    pub fn play(&self, sound: fn(&AudioHandles) -> &Handle<AudioSource>, volume: f64) {
+        // The attract mode demo plays silently
+        if self.demo_mode.is_some() {
+            return;
+        }
+        if let (Some(audio), Some(audio_handles), Some(audio_settings)) =
+            (&self.audio, &self.audio_handles, &self.audio_settings)
+        {
+            audio
+                .play(sound(audio_handles).clone())
+                .with_volume(audio_settings.sfx_volume(volume));
+        }
+    }
+}
 
 #[derive(Resource)]
 pub struct Instances {
@@ -18,6 +111,19 @@ pub struct Instances {
     falling: (Handle<AudioInstance>, f64),
 }
 
+impl Instances {
+    fn tracks(&self) -> [&(Handle<AudioInstance>, f64); 6] {
+        [
+            &self.background_beat,
+            &self.millipede,
+            &self.spider,
+            &self.scorpion,
+            &self.highhat,
+            &self.falling,
+        ]
+    }
+}
+
 #[derive(Resource)]
 pub struct AudioHandles {
     pub shoot: Handle<AudioSource>,
@@ -98,17 +204,35 @@ pub fn set_volume(
     game_state: Res<State<GameState>>,
     beetle_query: Query<(), With<Beetle>>,
     player_q: Query<(), With<Player>>,
+    audio_settings: Res<AudioSettings>,
+    demo_mode: Option<Res<DemoMode>>,
 ) {
+    // The attract mode demo plays silently
+    let audio_settings = AudioSettings {
+        muted: audio_settings.muted || demo_mode.is_some(),
+        ..*audio_settings
+    };
+
+    // Background beat
+    let beat_volume = audio_settings.music_volume(BEAT_VOLUME);
+    if instances.background_beat.1 != beat_volume {
+        if let Some(instance) = audio_instances.get_mut(&instances.background_beat.0) {
+            instance.set_volume(beat_volume, AudioTween::default());
+            instances.background_beat.1 = beat_volume;
+        }
+    }
+
+    let millipede_volume = audio_settings.music_volume(MILLIPEDE_VOLUME);
     let millipede_handle = &instances.millipede.0;
     // Millipede
     if !millipede_query.is_empty() && app_state.get() == &AppState::InGame {
-        if instances.millipede.1 != MILLIPEDE_VOLUME {
+        if instances.millipede.1 != millipede_volume {
             if let Some(instance) = audio_instances.get_mut(millipede_handle) {
                 instance.set_volume(
-                    MILLIPEDE_VOLUME,
+                    millipede_volume,
                     AudioTween::linear(Duration::from_secs_f32(0.5)),
                 );
-                instances.millipede.1 = MILLIPEDE_VOLUME;
+                instances.millipede.1 = millipede_volume;
             }
         }
     } else {
@@ -120,16 +244,17 @@ pub fn set_volume(
         }
     }
 
+    let spider_volume = audio_settings.music_volume(SPIDER_VOLUME);
     let spider_handle = &instances.spider.0;
     // Spider
     if !spider_query.is_empty() && app_state.get() == &AppState::InGame {
-        if instances.spider.1 != SPIDER_VOLUME {
+        if instances.spider.1 != spider_volume {
             if let Some(instance) = audio_instances.get_mut(spider_handle) {
                 instance.set_volume(
-                    SPIDER_VOLUME,
+                    spider_volume,
                     AudioTween::linear(Duration::from_secs_f32(0.5)),
                 );
-                instances.spider.1 = SPIDER_VOLUME;
+                instances.spider.1 = spider_volume;
             }
         }
     } else {
@@ -141,13 +266,14 @@ pub fn set_volume(
         }
     }
 
+    let scorpion_volume = audio_settings.music_volume(SCORPION_VOLUME);
     let scorpion_handle = &instances.scorpion.0;
     // Scorpion
     if !scorpion_query.is_empty() && app_state.get() == &AppState::InGame {
-        if instances.scorpion.1 != SCORPION_VOLUME && app_state.get() == &AppState::InGame {
+        if instances.scorpion.1 != scorpion_volume && app_state.get() == &AppState::InGame {
             if let Some(instance) = audio_instances.get_mut(scorpion_handle) {
-                instance.set_volume(SCORPION_VOLUME, AudioTween::default());
-                instances.scorpion.1 = SCORPION_VOLUME;
+                instance.set_volume(scorpion_volume, AudioTween::default());
+                instances.scorpion.1 = scorpion_volume;
             }
         }
     } else {
@@ -158,13 +284,15 @@ pub fn set_volume(
             }
         }
     }
+
+    let highhat_volume = audio_settings.music_volume(BACKGROUND_VOLUME);
     let highhat_handle = &instances.highhat.0;
     match *app_state.get() {
         AppState::InGame => {
-            if instances.highhat.1 != BACKGROUND_VOLUME && game_state.get() == &GameState::Running {
+            if instances.highhat.1 != highhat_volume && game_state.get() == &GameState::Running {
                 if let Some(instance) = audio_instances.get_mut(highhat_handle) {
-                    instance.set_volume(BACKGROUND_VOLUME, AudioTween::default());
-                    instances.highhat.1 = BACKGROUND_VOLUME;
+                    instance.set_volume(highhat_volume, AudioTween::default());
+                    instances.highhat.1 = highhat_volume;
                 }
             }
         }
@@ -176,18 +304,23 @@ pub fn set_volume(
         }
     }
 
+    let falling_volume = audio_settings.music_volume(FALLING_VOLUME);
     let falling_handle = &instances.falling.0;
     if !beetle_query.is_empty() && app_state.get() == &AppState::InGame && !player_q.is_empty() {
-        if instances.falling.1 != FALLING_VOLUME {
+        if instances.falling.1 != falling_volume {
             if let Some(instance) = audio_instances.get_mut(falling_handle) {
-                instance.seek_to(0.0); // Restart
-                instance.set_volume(FALLING_VOLUME, AudioTween::default());
-                instance.resume(AudioTween::default());
-                instances.falling.1 = FALLING_VOLUME;
+                // A volume change from the pause menu leaves the track paused
+                // where it was, for `resume_tracks` to pick up
+                if game_state.get() == &GameState::Running {
+                    instance.seek_to(0.0); // Restart
+                    instance.resume(AudioTween::default());
+                }
+                instance.set_volume(falling_volume, AudioTween::default());
+                instances.falling.1 = falling_volume;
             }
         }
     } else {
-        if instances.falling.1 == FALLING_VOLUME {
+        if instances.falling.1 != 0.0 {
             if let Some(instance) = audio_instances.get_mut(falling_handle) {
                 instance.pause(AudioTween::default());
                 instance.set_volume(0.0, AudioTween::default());
@@ -232,3 +365,51 @@ pub fn sync_audio(
         }
     }
 }
+
+// Slows the music down along with the enemies
+pub fn set_playback_rate(
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    instances: Res<Instances>,
+    slow_motion: Res<SlowMotion>,
+    app_state: Res<State<AppState>>,
+    mut playback_rate: Local<Option<f64>>,
+) {
+    let rate = if slow_motion.is_active() && *app_state.get() == AppState::InGame {
+        SLOW_MOTION_PLAYBACK_RATE
+    } else {
+        1.0
+    };
+    if *playback_rate == Some(rate) {
+        return;
+    }
+    for (handle, _) in instances.tracks() {
+        if let Some(instance) = audio_instances.get_mut(handle) {
+            instance.set_playback_rate(rate, AudioTween::linear(Duration::from_secs_f32(0.3)));
+        }
+    }
+    *playback_rate = Some(rate);
+}
+
+// Stops every looping track while the game is paused
+pub fn pause_tracks(mut audio_instances: ResMut<Assets<AudioInstance>>, instances: Res<Instances>) {
+    for (handle, _) in instances.tracks() {
+        if let Some(instance) = audio_instances.get_mut(handle) {
+            instance.pause(AudioTween::default());
+        }
+    }
+}
+
+pub fn resume_tracks(
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    instances: Res<Instances>,
+) {
+    for (handle, volume) in instances.tracks() {
+        // The falling track only plays while a beetle is falling
+        if *handle == instances.falling.0 && *volume == 0.0 {
+            continue;
+        }
+        if let Some(instance) = audio_instances.get_mut(handle) {
+            instance.resume(AudioTween::default());
+        }
+    }
+}
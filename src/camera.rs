@@ -1,17 +1,14 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
+
+use crate::game::playfield::Playfield;
 
 #[derive(Component)]
 pub struct GameCamera;
 
-pub fn spawn_game_camera(
-    mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-) {
-    let window = window_query.get_single().unwrap();
-
+pub fn spawn_game_camera(mut commands: Commands, playfield: Res<Playfield>) {
     commands.spawn((
         Camera2dBundle {
-            transform: Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0),
+            transform: Transform::from_xyz(playfield.width / 2.0, playfield.height / 2.0, 0.0),
             camera: Camera {
                 clear_color: ClearColorConfig::Custom(Color::BLACK),
                 ..default()
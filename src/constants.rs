@@ -1,53 +1,61 @@
 use crate::game::assets::AnimationIndices;
 use bevy::prelude::*;
 
-/// App Controls
-pub const MENU_KEY: KeyCode = KeyCode::Escape;
+// Balance numbers such as speeds, spawn rates and rewards are in
+// assets/tuning/default.tuning.ron
 
-/// Player Controls
-pub const SHOOT_KEY: KeyCode = KeyCode::Space;
-pub const LEFT: KeyCode = KeyCode::KeyA;
-pub const RIGHT: KeyCode = KeyCode::KeyD;
-pub const UP: KeyCode = KeyCode::KeyW;
-pub const DOWN: KeyCode = KeyCode::KeyS;
+/// Simulation
+pub const FIXED_TICK_RATE: f64 = 60.0; // Ticks per second
+pub const MAX_LEVEL_GROWTH: usize = 1000; // Levels past the tuning's table that still get harder
+
+// Controls are rebindable, the defaults are in src/game/actions.rs
+
+/// Attract Mode
+pub const ATTRACT_DELAY: f32 = 20.0; // Seconds idle on the main menu before the demo starts
+
+/// Autoplay Bot
+pub const BOT_THREAT_DISTANCE: f32 = 80.0; // How close an enemy gets before the bot backs away
+pub const BOT_DODGE_WEIGHT: f32 = 3.0;
+
+/// Learning Environment
+pub const ENV_LIFE_LOST_PENALTY: f32 = 500.0; // Reward taken away for each life lost
+
+/// Trackball
+pub const TRACKBALL_DEFAULT_SENSITIVITY: f32 = 1.0;
+pub const TRACKBALL_SENSITIVITY_STEP: f32 = 0.1;
+pub const TRACKBALL_MAX_SENSITIVITY: f32 = 5.0;
+pub const TRACKBALL_DEFAULT_INERTIA: f32 = 0.85;
+pub const TRACKBALL_INERTIA_STEP: f32 = 0.05;
+pub const TRACKBALL_MAX_INERTIA: f32 = 0.95;
 
 // Attributes
-pub const PLAYER_SPEED: f32 = 275.0;
 pub const PLAYER_SIZE: f32 = 16.0;
 
 /// Projectile
-pub const PROJECTILE_SPEED: f32 = 1300.0;
-pub const PROJECTILE_ACCELERATION: f32 = 1.0;
 pub const PROJECTILE_SIZE: f32 = 10.0;
-pub const STARTING_LIVES: u8 = 3;
 
 // Map
+pub const PLAYFIELD_WIDTH: f32 = 480.0;
+pub const PLAYFIELD_HEIGHT: f32 = 640.0;
 pub const TOP_BOUND: f32 = 100.0;
 pub const DOWNTIMER: f32 = 2.0;
 pub const PLAYER_SPAWN_Y: f32 = 20.0;
 pub const GAMEOVER_TIMER: f32 = 1.0;
+// Side of a cell in the collision grid
+pub const SPATIAL_GRID_CELL_SIZE: f32 = 32.0;
 
 /// Mushrooms
 pub const MUSHROOM_SIZE: f32 = 11.0;
-pub const MUSHROOM_MAX_AMOUNT: u8 = 100; // 100
-pub const MUSHROOM_MIN_AMOUNT: u8 = 70; // 70
+pub const MUSHROOM_CELL_SIZE: f32 = SEGMENT_SIZE;
 pub const SPAWN_MARGIN: f32 = 20.0;
-pub const MUSHROOM_HEALTH: i8 = 3;
 pub const MUSHROOM_POISON_COLOR: Color = Color::rgb(0.2, 0.5, 1.0);
 pub const MUSHROOM_FRESH_COLOR: Color = Color::rgb(1.0, 0.1, 0.7);
 
 /// Millipede
-pub const MILLIPEDE_STARTING_LENGTH: usize = 9;
-pub const MILLIPEDE_MAX_LENGTH: usize = 40;
-pub const MILLIPEDE_SPEED: f32 = 300.0;
-pub const MILLIPEDE_MAX_SPEED: f32 = 500.0;
-pub const MILLIPEDE_SPEED_CHANGE_RATE: f32 = 1.025;
 pub const SEGMENT_SIZE: f32 = 16.0;
 pub const SEGMENT_SPACING: f32 = 15.0;
 pub const DROP_AMOUNT: f32 = SEGMENT_SIZE / 2.0 + 1.0;
 pub const PUSH_BACK_AMOUNT: f32 = 10.0;
-pub const SEGMENT_SPAWN_TIMER_DURATION: f32 = 9.0;
-pub const SEGMENT_DROP_RATE: f64 = 0.01;
 pub const MILLIPEDE_HEAD_COLOR: Color = Color::rgb(1.0, 0.4, 0.6);
 pub const MILLIPEDE_HEAD_COLOR_POISONED: Color = Color::rgb(0.3, 0.1, 1.0);
 pub const SEGMENT_ANIMATION_SPEED: f32 = 0.05;
@@ -56,35 +64,38 @@ pub const SEGMENT_ANIMATION_SPEED: f32 = 0.05;
 pub const EXPLOSION_DURATION: f32 = 0.35;
 pub const EXPLOSION_SIZE: f32 = 1.5;
 
-// Beetle
-pub const BEETLE_SPEED: f32 = 500.0;
-pub const BEETLE_SPAWN_RATE: u8 = 5;
-
 // Spider
-pub const SPIDER_TIMER: f32 = 10.0;
-pub const SPIDER_SPAWN_RATE: f64 = 0.5;
-pub const SPIDER_AVERAGE_SPAWN_HEIGHT: f32 = 500.0;
-pub const SPIDER_DIRECTION_CHANGE_RATE: f64 = 0.07;
-pub const SPIDER_SPEED: f32 = 275.0;
 pub const SPIDER_SIZE: f32 = 16.0;
-pub const SPIDER_ATTACK_RATE: f64 = 0.1;
-pub const SPIDER_LEAVE_RATE: f64 = 0.8;
-pub const SPIDER_EAT_RATE: f64 = 0.05;
 
 // Scorpion
-pub const SCORPION_SPAWN_RATE: f64 = 0.0005;
 pub const SCORPION_SPAWN_HEIGHT: f32 = 100.0;
-pub const SCORPION_SPEED: f32 = 250.0;
 pub const SCORPION_SIZE: f32 = 16.0;
-pub const MUSHROOM_CONVERSION_RATE: f64 = 0.02;
 
-// Scoring
-pub const MUSHROOM_REWARD: u32 = 1;
-pub const SEGMENT_REWARD: u32 = 10;
-pub const HEAD_REWARD: u32 = 100;
-pub const BEETLE_REWARD: u32 = 50;
-pub const SPIDER_REWARD: u32 = 300;
-pub const SCORPION_REWARD: u32 = 1000;
+// Earwig
+pub const EARWIG_SIZE: f32 = 16.0;
+pub const EARWIG_TOP_MARGIN: f32 = 32.0; // Keeps its lane clear of the HUD
+
+// Inchworm
+pub const INCHWORM_SIZE: f32 = 16.0;
+pub const SLOW_MOTION_TINT: Color = Color::rgba(0.3, 0.5, 1.0, 0.12);
+pub const SLOW_MOTION_TEXT_COLOR: Color = Color::rgb(0.5, 0.7, 1.0);
+
+// Dragonfly
+pub const DRAGONFLY_SIZE: f32 = 16.0;
+pub const DRAGONFLY_ZIGZAG_WIDTH: f32 = 48.0;
+
+// Swarm
+pub const BEE_SIZE: f32 = 16.0;
+pub const BEE_FORMATION_COLUMNS: u32 = 8;
+pub const BEE_FORMATION_SPACING: f32 = 40.0;
+pub const BEE_SWAY_WIDTH: f32 = 40.0;
+pub const BEE_SWAY_SPEED: f32 = 2.0; // Radians per second
+pub const SWARM_TALLY_TIME: f32 = 3.0;
+
+// High Scores
+pub const HIGH_SCORE_COUNT: usize = 10;
+pub const INITIALS_LENGTH: usize = 3;
+pub const INITIALS_CURSOR_COLOR: Color = Color::YELLOW;
 
 // UI
 pub const TEXT_COLOR: Color = Color::rgb(102.0 / 255.0, 255.0 / 255.0, 143.0 / 255.0);
@@ -100,11 +111,20 @@ pub const BACKGROUND_VOLUME: f64 = 0.7;
 pub const MILLIPEDE_VOLUME: f64 = 0.6;
 pub const SPIDER_VOLUME: f64 = 0.4;
 pub const SCORPION_VOLUME: f64 = 0.4;
+pub const SLOW_MOTION_PLAYBACK_RATE: f64 = 0.8;
 pub const FALLING_VOLUME: f64 = 0.3;
 pub const SPAWN_VOLUME: f64 = 0.4;
+pub const BEAT_VOLUME: f64 = 1.0;
+pub const SHOOT_VOLUME: f64 = 0.2;
+pub const EXPLOSION_VOLUME: f64 = 0.4;
+pub const VOLUME_STEP: f64 = 0.1;
 
 // Animation
 pub const MUSHROOM_ANIMATION_INDICES: AnimationIndices = AnimationIndices { first: 1, last: 4 };
 pub const EXPLOSION_ANIMATION_INDICES: AnimationIndices = AnimationIndices { first: 1, last: 3 };
 pub const SPIDER_ANIMATION_INDICES: AnimationIndices = AnimationIndices { first: 0, last: 2 };
 pub const SCORPION_ANIMATION_TIME: f32 = 0.07;
+pub const EARWIG_ANIMATION_TIME: f32 = 0.12;
+pub const INCHWORM_ANIMATION_TIME: f32 = 0.3;
+pub const DRAGONFLY_ANIMATION_TIME: f32 = 0.05;
+pub const BEE_ANIMATION_TIME: f32 = 0.05;
@@ -1,6 +1,5 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-use std::env;
 
 use crate::*;
 
@@ -10,28 +9,14 @@ impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         if cfg!(debug_assertions) {
             // World inspector plugin
-            let inspector_enabled = env::args().any(|arg| arg == "--inspector");
+            let inspector_enabled = args::flag("--inspector");
             if inspector_enabled {
                 app.add_plugins(WorldInspectorPlugin::new());
             }
             app.add_systems(
                 Update,
                 kill_player.run_if(in_state(crate::game::PlayerState::Alive)),
-            )
-            .add_systems(Update, toggle_pause.run_if(in_state(AppState::InGame)));
-        }
-    }
-}
-
-fn toggle_pause(
-    state: Res<State<crate::GameState>>,
-    mut next_state: ResMut<NextState<crate::GameState>>,
-    input: Res<ButtonInput<KeyCode>>,
-) {
-    if input.just_pressed(constants::MENU_KEY) {
-        match state.get() {
-            GameState::Running => next_state.set(GameState::Paused),
-            GameState::Paused => next_state.set(GameState::Running),
+            );
         }
     }
 }
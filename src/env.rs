@@ -0,0 +1,293 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration};
+
+use crate::{
+    constants::ENV_LIFE_LOST_PENALTY,
+    game::{
+        beetle::Beetle,
+        dragonfly::Dragonfly,
+        earwig::Earwig,
+        inchworm::{Inchworm, SlowMotion},
+        input::{PlayerController, PlayerInput},
+        interpolation::Interpolated,
+        level::Level,
+        millipede::{HeadState, Segment},
+        player::{Lives, Player},
+        projectile::PlayerProjectile,
+        scorpion::Scorpion,
+        shroom::Mushroom,
+        spider::Spider,
+        swarm::Bee,
+        GameOptions, Health, Score,
+    },
+    headless, AppState,
+};
+
+// The game as an environment for training and benchmarking agents. Each step
+// plays one fixed tick of the real game with the agent's input, e.g.
+//
+//     let mut env = MillipedeEnv::new(42);
+//     let (observation, reward, done) = env.step(PlayerInput { movement: Vec2::X, fire: true });
+pub struct MillipedeEnv {
+    app: App,
+    tuning: String,
+}
+
+// What the agent can see after a step. Positions are in playfield pixels with
+// the origin at the bottom left
+#[derive(Clone, Debug, Default)]
+pub struct Observation {
+    // None while the player is dead
+    pub player: Option<Vec2>,
+    pub projectile: Option<Vec2>,
+    pub segments: Vec<SegmentObservation>,
+    pub mushrooms: Vec<MushroomObservation>,
+    pub spider: Option<Vec2>,
+    pub scorpion: Option<Vec2>,
+    pub beetle: Option<Vec2>,
+    pub earwig: Option<Vec2>,
+    pub inchworm: Option<Vec2>,
+    pub dragonfly: Option<Vec2>,
+    pub bees: Vec<Vec2>,
+    // Seconds left of the inchworm's slow motion
+    pub slow_motion: f32,
+    pub score: u32,
+    pub lives: u8,
+    pub level: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SegmentObservation {
+    pub position: Vec2,
+    pub head: bool,
+    // Poisoned heads dive straight for the bottom
+    pub poisoned: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MushroomObservation {
+    pub position: Vec2,
+    pub health: i8,
+    pub poisoned: bool,
+}
+
+impl MillipedeEnv {
+    pub fn new(seed: u64) -> Self {
+        Self::with_tuning(seed, "default")
+    }
+
+    // Plays with one of the files in `assets/tuning`, e.g. "hard"
+    pub fn with_tuning(seed: u64, tuning: &str) -> Self {
+        MillipedeEnv {
+            app: start_game(external_options(seed, tuning)),
+            tuning: tuning.to_string(),
+        }
+    }
+
+    // Throws the current game away and starts a new one
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.app = start_game(external_options(seed, &self.tuning));
+        self.observe()
+    }
+
+    // Plays one tick with the given input. The reward is the points scored,
+    // less a penalty for each life lost. Once done, steps change nothing until
+    // the next reset
+    pub fn step(&mut self, action: PlayerInput) -> (Observation, f32, bool) {
+        if self.done() {
+            return (self.observe(), 0.0, true);
+        }
+        let score_before = self.app.world.resource::<Score>().0;
+        let lives_before = self.app.world.resource::<Lives>().0;
+
+        *self.app.world.resource_mut::<PlayerInput>() = action;
+        self.app.update();
+
+        let score = self.app.world.resource::<Score>().0;
+        let lives = self.app.world.resource::<Lives>().0;
+        let points = score as f32 - score_before as f32;
+        let penalty = lives_before.saturating_sub(lives) as f32 * ENV_LIFE_LOST_PENALTY;
+        (self.observe(), points - penalty, self.done())
+    }
+
+    // The game ends the moment the last life is lost
+    pub fn done(&self) -> bool {
+        self.app.world.resource::<Lives>().0 == 0
+            || *self.app.world.resource::<State<AppState>>().get() == AppState::GameOver
+    }
+
+    pub fn observe(&mut self) -> Observation {
+        let world = &mut self.app.world;
+        let mut observation = Observation {
+            score: world.resource::<Score>().0,
+            lives: world.resource::<Lives>().0,
+            level: world.resource::<Level>().0,
+            slow_motion: world.resource::<SlowMotion>().remaining_secs(),
+            ..default()
+        };
+
+        observation.player = first_position::<Player>(world);
+        observation.projectile = first_position::<PlayerProjectile>(world);
+        observation.spider = first_position::<Spider>(world);
+        observation.scorpion = first_position::<Scorpion>(world);
+        observation.beetle = first_position::<Beetle>(world);
+        observation.earwig = first_position::<Earwig>(world);
+        observation.inchworm = first_position::<Inchworm>(world);
+        observation.dragonfly = first_position::<Dragonfly>(world);
+
+        let mut bee_query =
+            world.query_filtered::<(&Transform, Option<&Interpolated>), With<Bee>>();
+        observation.bees = bee_query
+            .iter(world)
+            .map(|(transform, interpolated)| simulated_position(transform, interpolated))
+            .collect();
+
+        let mut segment_query = world.query::<(&Transform, Option<&Interpolated>, &Segment)>();
+        observation.segments = segment_query
+            .iter(world)
+            .map(|(transform, interpolated, segment)| SegmentObservation {
+                position: simulated_position(transform, interpolated),
+                head: matches!(segment, Segment::Head { .. }),
+                poisoned: matches!(
+                    segment,
+                    Segment::Head {
+                        head_state: HeadState::Poisoned,
+                        ..
+                    }
+                ),
+            })
+            .collect();
+
+        let mut mushroom_query = world.query::<(&Transform, &Health, &Mushroom)>();
+        observation.mushrooms = mushroom_query
+            .iter(world)
+            .map(|(transform, health, mushroom)| MushroomObservation {
+                position: transform.translation.truncate(),
+                health: health.0,
+                poisoned: *mushroom == Mushroom::Poison,
+            })
+            .collect();
+
+        observation
+    }
+}
+
+// The agent feeds the input and nothing is read from the host's command line
+fn external_options(seed: u64, tuning: &str) -> GameOptions {
+    GameOptions {
+        seed: Some(seed),
+        tuning: tuning.to_string(),
+        controller: PlayerController::External,
+        ..default()
+    }
+}
+
+// Builds a headless game and enters it without simulating a tick, so the first
+// step is the game's first tick
+fn start_game(options: GameOptions) -> App {
+    let mut app = headless::build_app(options);
+    app.finish();
+    app.cleanup();
+
+    let timestep = app.world.resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    app.update();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    app
+}
+
+fn first_position<T: Component>(world: &mut World) -> Option<Vec2> {
+    let mut query = world.query_filtered::<(&Transform, Option<&Interpolated>), With<T>>();
+    query
+        .iter(world)
+        .next()
+        .map(|(transform, interpolated)| simulated_position(transform, interpolated))
+}
+
+// Moving sprites are drawn between ticks, but the agent sees where the
+// simulation actually has them
+fn simulated_position(transform: &Transform, interpolated: Option<&Interpolated>) -> Vec2 {
+    interpolated
+        .map_or(transform.translation, Interpolated::simulated_translation)
+        .truncate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::replay::{Replay, ReplayRecorder},
+        GameState,
+    };
+
+    const TICKS: usize = 3000;
+    const PAUSE_TICKS: usize = 60;
+
+    // Plays with a simple policy, pausing at the start of every downtime
+    // between lives and levels, where the round timers are running
+    #[test]
+    fn replay_with_pauses_matches_recording() {
+        let mut env = MillipedeEnv::new(11);
+        let mut observation = env.observe();
+        let mut was_down = false;
+        let mut pauses = 0;
+        for _ in 0..TICKS {
+            let down = observation.player.is_none() || observation.segments.is_empty();
+            if down && !was_down {
+                pause(&mut env.app);
+                pauses += 1;
+            }
+            was_down = down;
+
+            let (next_observation, _, done) = env.step(chase_lowest_head(&observation));
+            observation = next_observation;
+            if done {
+                break;
+            }
+        }
+        assert!(pauses > 0, "the run should pause during a downtime");
+
+        let timestep = env.app.world.resource::<Time<Fixed>>().timestep();
+        let recording = env.app.world.resource::<ReplayRecorder>().replay(timestep);
+        let replay = Replay::from_bytes(&recording.to_bytes()).expect("replay should decode");
+
+        let ticks = replay.frames.len();
+        let mut app = start_game(GameOptions {
+            replay: Some(replay),
+            ..default()
+        });
+        for _ in 0..ticks {
+            app.update();
+        }
+
+        assert_eq!(app.world.resource::<Score>().0, observation.score);
+        assert_eq!(app.world.resource::<Lives>().0, observation.lives);
+        assert_eq!(app.world.resource::<Level>().0, observation.level);
+    }
+
+    fn pause(app: &mut App) {
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Paused);
+        for _ in 0..PAUSE_TICKS {
+            app.update();
+        }
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Running);
+    }
+
+    fn chase_lowest_head(observation: &Observation) -> PlayerInput {
+        let player = observation.player.unwrap_or_default();
+        let target = observation
+            .segments
+            .iter()
+            .filter(|segment| segment.head)
+            .map(|segment| segment.position)
+            .min_by(|a, b| a.y.total_cmp(&b.y))
+            .unwrap_or(player);
+        PlayerInput {
+            movement: Vec2::new(((target.x - player.x) / 8.0).clamp(-1.0, 1.0), -1.0),
+            fire: true,
+        }
+    }
+}
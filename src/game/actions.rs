@@ -0,0 +1,239 @@
+use super::*;
+use bevy::ecs::system::SystemParam;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const BINDINGS_KEY: &str = "bindings";
+
+// Everything the player can do. Gameplay and menus ask for actions instead of
+// keys so the controls can be rebound
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Fire,
+    Menu,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Fire,
+        Action::Menu,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Left",
+            Action::MoveRight => "Right",
+            Action::MoveUp => "Up",
+            Action::MoveDown => "Down",
+            Action::Fire => "Fire",
+            Action::Menu => "Menu",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Button(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn label(self) -> String {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{:?}", key);
+                let name = name.strip_prefix("Key").unwrap_or(&name);
+                name.strip_prefix("Digit").unwrap_or(name).to_string()
+            }
+            Binding::Button(button) => format!("Pad {:?}", button),
+        }
+    }
+}
+
+// The keys and gamepad buttons bound to each action. Saved as RON whenever
+// they are changed
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bindings(pub BTreeMap<Action, Vec<Binding>>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use GamepadButtonType::*;
+        Bindings(BTreeMap::from([
+            (
+                Action::MoveLeft,
+                vec![
+                    Binding::Key(KeyCode::KeyA),
+                    Binding::Key(KeyCode::ArrowLeft),
+                    Binding::Button(DPadLeft),
+                ],
+            ),
+            (
+                Action::MoveRight,
+                vec![
+                    Binding::Key(KeyCode::KeyD),
+                    Binding::Key(KeyCode::ArrowRight),
+                    Binding::Button(DPadRight),
+                ],
+            ),
+            (
+                Action::MoveUp,
+                vec![
+                    Binding::Key(KeyCode::KeyW),
+                    Binding::Key(KeyCode::ArrowUp),
+                    Binding::Button(DPadUp),
+                ],
+            ),
+            (
+                Action::MoveDown,
+                vec![
+                    Binding::Key(KeyCode::KeyS),
+                    Binding::Key(KeyCode::ArrowDown),
+                    Binding::Button(DPadDown),
+                ],
+            ),
+            (
+                Action::Fire,
+                vec![Binding::Key(KeyCode::Space), Binding::Button(South)],
+            ),
+            (
+                Action::Menu,
+                vec![Binding::Key(KeyCode::Escape), Binding::Button(Start)],
+            ),
+        ]))
+    }
+}
+
+impl Bindings {
+    pub fn load() -> Self {
+        let mut bindings = Bindings::default();
+        let Some(saved) = crate::storage::load(BINDINGS_KEY) else {
+            return bindings;
+        };
+        match ron::from_str::<Bindings>(&saved) {
+            // Actions missing from the save keep their defaults
+            Ok(saved) => bindings.0.extend(saved.0),
+            Err(error) => warn!("Could not read saved bindings: {}", error),
+        }
+        bindings
+    }
+
+    pub fn save(&self) {
+        let saved = match ron::to_string(self) {
+            Ok(saved) => saved,
+            Err(error) => {
+                warn!("Could not save bindings: {}", error);
+                return;
+            }
+        };
+        if let Err(error) = crate::storage::save(BINDINGS_KEY, &saved) {
+            warn!("Could not save bindings: {}", error);
+        }
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0
+            .get(&action)
+            .map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    // Binds the key or button to the action, or unbinds it if it already was.
+    // An action always keeps at least one binding so the menus stay usable
+    pub fn toggle(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        if let Some(index) = bindings.iter().position(|bound| *bound == binding) {
+            if bindings.len() > 1 {
+                bindings.remove(index);
+            }
+            return;
+        }
+
+        // A key only does one thing, so it moves over from any other action.
+        // It stays put if it's all that action has
+        let only_binding_elsewhere = self.0.iter().any(|(other_action, other_bindings)| {
+            *other_action != action && other_bindings.as_slice() == [binding]
+        });
+        if only_binding_elsewhere {
+            return;
+        }
+        for other_bindings in self.0.values_mut() {
+            other_bindings.retain(|bound| *bound != binding);
+        }
+        self.0.entry(action).or_default().push(binding);
+    }
+}
+
+// Reads actions through the current bindings, from the keyboard and the active
+// gamepad
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+    active_gamepad: Res<'w, ActiveGamepad>,
+    bindings: Res<'w, Bindings>,
+}
+
+impl<'w> ActionInput<'w> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.bindings
+            .get(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key) => self.keyboard_input.pressed(*key),
+                Binding::Button(button) => {
+                    self.active_gamepad.pressed(&self.gamepad_buttons, *button)
+                }
+            })
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.bindings
+            .get(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key) => self.keyboard_input.just_pressed(*key),
+                Binding::Button(button) => self
+                    .active_gamepad
+                    .just_pressed(&self.gamepad_buttons, *button),
+            })
+    }
+
+    // The left stick of the active gamepad
+    pub fn stick(&self) -> Vec2 {
+        self.active_gamepad.stick(&self.gamepad_axes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(bindings: &Bindings, action: Action, key: KeyCode) -> bool {
+        bindings.get(action).contains(&Binding::Key(key))
+    }
+
+    #[test]
+    fn toggle_never_binds_a_key_twice() {
+        let mut bindings = Bindings::default();
+
+        // Menu still has the Start button, so Escape moves over to Fire
+        bindings.toggle(Action::Fire, Binding::Key(KeyCode::Escape));
+        assert!(bound(&bindings, Action::Fire, KeyCode::Escape));
+        assert!(!bound(&bindings, Action::Menu, KeyCode::Escape));
+
+        // With Space all Fire has left, it can't be taken for Menu
+        bindings.toggle(Action::Fire, Binding::Key(KeyCode::Escape));
+        bindings.toggle(Action::Fire, Binding::Button(GamepadButtonType::South));
+        bindings.toggle(Action::Menu, Binding::Key(KeyCode::Space));
+        assert!(bound(&bindings, Action::Fire, KeyCode::Space));
+        assert!(!bound(&bindings, Action::Menu, KeyCode::Space));
+    }
+}
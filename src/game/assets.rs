@@ -15,12 +15,46 @@ pub struct GameAssets {
     pub explosion_layout: Handle<TextureAtlasLayout>,
     pub scorpion_texture: Handle<Image>,
     pub scorpion_layout: Handle<TextureAtlasLayout>,
+    pub earwig_texture: Handle<Image>,
+    pub earwig_layout: Handle<TextureAtlasLayout>,
+    pub inchworm_texture: Handle<Image>,
+    pub inchworm_layout: Handle<TextureAtlasLayout>,
+    pub dragonfly_texture: Handle<Image>,
+    pub dragonfly_layout: Handle<TextureAtlasLayout>,
+    pub bee_texture: Handle<Image>,
+    pub bee_layout: Handle<TextureAtlasLayout>,
     pub font: Handle<Font>,
 }
 
 impl FromWorld for GameAssets {
     fn from_world(world: &mut World) -> Self {
-        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        // Without an asset server (e.g. headless) entities just get empty handles
+        let Some(asset_server) = world.get_resource::<AssetServer>() else {
+            return GameAssets {
+                player_texture: default(),
+                segment_texture: default(),
+                segment_layout: default(),
+                beetle_texture: default(),
+                projectile_texture: default(),
+                shroom_texture: default(),
+                shroom_layout: default(),
+                spider_texture: default(),
+                spider_layout: default(),
+                explosion_texture: default(),
+                explosion_layout: default(),
+                scorpion_texture: default(),
+                scorpion_layout: default(),
+                earwig_texture: default(),
+                earwig_layout: default(),
+                inchworm_texture: default(),
+                inchworm_layout: default(),
+                dragonfly_texture: default(),
+                dragonfly_layout: default(),
+                bee_texture: default(),
+                bee_layout: default(),
+                font: default(),
+            };
+        };
 
         // Load the textures
         let player_texture = asset_server.load("textures/snake.png");
@@ -31,6 +65,10 @@ impl FromWorld for GameAssets {
         let spider_texture = asset_server.load("textures/spider.png");
         let explosion_texture = asset_server.load("textures/explosions.png");
         let scorpion_texture = asset_server.load("textures/scorpions.png");
+        let earwig_texture = asset_server.load("textures/earwigs.png");
+        let inchworm_texture = asset_server.load("textures/inchworms.png");
+        let dragonfly_texture = asset_server.load("textures/dragonflies.png");
+        let bee_texture = asset_server.load("textures/bees.png");
         let font = asset_server.load("fonts/font.ttf");
 
         // Define the layout
@@ -56,6 +94,22 @@ impl FromWorld for GameAssets {
         let layout = TextureAtlasLayout::from_grid(Vec2::new(16.0, 16.0), 2, 1, None, None);
         let scorpion_layout = texture_atlas_layouts.add(layout);
 
+        // Earwig
+        let layout = TextureAtlasLayout::from_grid(Vec2::new(16.0, 16.0), 2, 1, None, None);
+        let earwig_layout = texture_atlas_layouts.add(layout);
+
+        // Inchworm
+        let layout = TextureAtlasLayout::from_grid(Vec2::new(16.0, 16.0), 2, 1, None, None);
+        let inchworm_layout = texture_atlas_layouts.add(layout);
+
+        // Dragonfly
+        let layout = TextureAtlasLayout::from_grid(Vec2::new(16.0, 16.0), 2, 1, None, None);
+        let dragonfly_layout = texture_atlas_layouts.add(layout);
+
+        // Bee
+        let layout = TextureAtlasLayout::from_grid(Vec2::new(16.0, 16.0), 2, 1, None, None);
+        let bee_layout = texture_atlas_layouts.add(layout);
+
         // Construct the GameAssets instance
         GameAssets {
             player_texture,
@@ -71,6 +125,14 @@ impl FromWorld for GameAssets {
             explosion_layout,
             scorpion_texture,
             scorpion_layout,
+            earwig_texture,
+            earwig_layout,
+            inchworm_texture,
+            inchworm_layout,
+            dragonfly_texture,
+            dragonfly_layout,
+            bee_texture,
+            bee_layout,
             font,
         }
     }
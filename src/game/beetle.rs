@@ -2,8 +2,8 @@
 // if the current amount of shrooms goes below the threshold
 
 use super::*;
-use crate::audio::AudioHandles;
-use bevy_kira_audio::{Audio, AudioControl};
+use crate::audio::SoundEffects;
+use bevy::ecs::system::SystemParam;
 use rand::*;
 
 #[derive(Component)]
@@ -25,19 +25,40 @@ impl Beetle {
                 ..default()
             },
             Name::from("Beetle"),
+            Interpolated::new(starting_transform.translation),
         ));
     }
 }
 
+// Whether the field is down to few enough mushrooms to send a beetle
+#[derive(SystemParam)]
+pub struct MushroomShortage<'w> {
+    mushroom_grid: Res<'w, MushroomGrid>,
+    tuning: Res<'w, Tuning>,
+}
+
+impl MushroomShortage<'_> {
+    pub fn is_short(&self) -> bool {
+        self.mushroom_grid.count() <= self.tuning.mushroom_min_amount as usize
+    }
+}
+
 pub fn spawn_beetle(
     mut commands: Commands,
-    shroom_amount: ResMut<ShroomAmount>,
-    window_q: Query<&Window, With<PrimaryWindow>>,
+    shortage: MushroomShortage,
+    playfield: Res<Playfield>,
     game_assets: Res<GameAssets>,
     beetle_q: Query<&Beetle>,
+    mut game_rng: ResMut<GameRng>,
+    game_vars: Res<GameVariables>,
 ) {
+    // Only run on levels with beetles
+    if !game_vars.beetle_enabled {
+        return;
+    }
+
     // Check if under the threshold
-    if shroom_amount.0 > MUSHROOM_MIN_AMOUNT {
+    if !shortage.is_short() {
         return;
     }
 
@@ -46,10 +67,9 @@ pub fn spawn_beetle(
         return;
     }
 
-    let window = window_q.get_single().unwrap();
     // Generate a random starting position
-    let x = rand::thread_rng().gen_range(0.0 + SPAWN_MARGIN..window.width() - SPAWN_MARGIN);
-    let y = window.height();
+    let x = game_rng.gen_range(0.0 + SPAWN_MARGIN..playfield.width - SPAWN_MARGIN);
+    let y = playfield.height;
 
     Beetle::spawn(&Transform::from_xyz(x, y, 0.0), &mut commands, &game_assets);
 }
@@ -63,17 +83,24 @@ pub fn despawn_beetle(mut commands: Commands, beetle_q: Query<(Entity, &Transfor
     }
 }
 
-pub fn move_beetle(mut beetle_q: Query<&mut Transform, With<Beetle>>, time: Res<Time>) {
+pub fn move_beetle(
+    mut beetle_q: Query<&mut Transform, With<Beetle>>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    slow_motion: Res<SlowMotion>,
+) {
     if let Ok(mut beetle_transform) = beetle_q.get_single_mut() {
-        beetle_transform.translation.y -= BEETLE_SPEED * time.delta_seconds();
+        beetle_transform.translation.y -=
+            tuning.beetle_speed * slow_motion.factor() * time.delta_seconds();
     }
 }
 
 pub fn beetle_spawn_shroom(
     beetle_q: Query<&Transform, With<Beetle>>,
     mut spawn_mushroom_ew: EventWriter<SpawnMushroomEvent>,
-    audio: Res<Audio>,
-    audio_handles: Res<AudioHandles>,
+    sound_effects: SoundEffects,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
 ) {
     if let Ok(beetle_transform) = beetle_q.get_single() {
         // Check if below boundary
@@ -82,8 +109,8 @@ pub fn beetle_spawn_shroom(
         }
 
         // Generate a random num, and spawn if hit
-        let num = rand::thread_rng().gen_range(1..=100);
-        if num >= BEETLE_SPAWN_RATE {
+        let num = game_rng.gen_range(1..=100);
+        if num >= tuning.beetle_spawn_rate {
             return;
         }
 
@@ -94,8 +121,6 @@ pub fn beetle_spawn_shroom(
             Transform::from_xyz(x, y, 0.0),
             MUSHROOM_FRESH_COLOR,
         ));
-        audio
-            .play(audio_handles.spawn.clone())
-            .with_volume(SPAWN_VOLUME);
+        sound_effects.play(|audio_handles| &audio_handles.spawn, SPAWN_VOLUME);
     }
 }
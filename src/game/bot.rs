@@ -0,0 +1,87 @@
+use super::*;
+
+// The enemies besides the millipede that the bot steps away from
+type Dangerous = Or<(
+    With<Spider>,
+    With<Scorpion>,
+    With<Beetle>,
+    With<Dragonfly>,
+    With<Bee>,
+)>;
+
+// A scripted player for the attract mode and for playtesting with `--autoplay`.
+// It keeps to the bottom, lines up under the lowest millipede head, steps
+// away from anything getting close and fires constantly
+pub fn drive_bot(
+    player_query: Query<&Transform, With<Player>>,
+    segment_query: Query<(&Transform, &Segment)>,
+    bee_query: Query<&Transform, With<Bee>>,
+    enemy_query: Query<&Transform, Dangerous>,
+    mut player_input: ResMut<PlayerInput>,
+    playfield: Res<Playfield>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        *player_input = PlayerInput::default();
+        return;
+    };
+    let position = player_transform.translation.truncate();
+
+    // Aim at the head closest to reaching the player, or the lowest bee
+    // during a swarm
+    let lowest_target = segment_query
+        .iter()
+        .filter(|(_, segment)| matches!(segment, Segment::Head { .. }))
+        .map(|(transform, _)| transform.translation)
+        .min_by(|a, b| a.y.total_cmp(&b.y))
+        .or_else(|| {
+            bee_query
+                .iter()
+                .map(|transform| transform.translation)
+                .min_by(|a, b| a.y.total_cmp(&b.y))
+        });
+    let target = Vec2::new(
+        lowest_target.map_or(playfield.width / 2.0, |target| target.x),
+        PLAYER_SIZE,
+    );
+    let mut movement = (target - position) / PLAYER_SIZE;
+
+    // Each threat pushes the player away, harder the closer it is
+    let threats = segment_query
+        .iter()
+        .map(|(transform, _)| transform)
+        .chain(enemy_query.iter());
+    for threat_transform in threats {
+        let away = position - threat_transform.translation.truncate();
+        let distance = away.length();
+        if distance < BOT_THREAT_DISTANCE {
+            let urgency = 1.0 - distance / BOT_THREAT_DISTANCE;
+            movement += away.normalize_or_zero() * urgency * BOT_DODGE_WEIGHT;
+        }
+    }
+
+    *player_input = PlayerInput {
+        movement: movement.clamp(Vec2::NEG_ONE, Vec2::ONE),
+        fire: true,
+    };
+}
+
+// Starts playing straight away when launched with `--autoplay`
+pub fn start_autoplay(mut next_app_state: ResMut<NextState<AppState>>) {
+    next_app_state.set(AppState::InGame);
+}
+
+// Reports each game and starts the next, so the bot can play unattended
+pub fn restart_autoplay(
+    score: Res<Score>,
+    level: Res<Level>,
+    game_rng: Res<GameRng>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    info!(
+        "Autoplay game over on level {} with a score of {} (seed {})",
+        level.0,
+        score.0,
+        game_rng.seed()
+    );
+    next_app_state.set(AppState::InGame);
+}
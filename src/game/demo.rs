@@ -0,0 +1,79 @@
+use super::*;
+use bevy::ecs::system::SystemParam;
+
+// Present while the attract mode demo is playing. The demo is a real game with
+// the player handed over to the bot
+#[derive(Resource)]
+pub struct DemoMode;
+
+// How long the main menu has been left alone
+#[derive(Resource)]
+pub struct AttractTimer(pub Timer);
+
+impl Default for AttractTimer {
+    fn default() -> Self {
+        AttractTimer(Timer::from_seconds(ATTRACT_DELAY, TimerMode::Once))
+    }
+}
+
+// Whether the player touched any control at all this frame
+#[derive(SystemParam)]
+pub struct AnyInput<'w> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    mouse_buttons: Res<'w, ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+    touches: Res<'w, Touches>,
+}
+
+impl AnyInput<'_> {
+    pub fn just_pressed(&self) -> bool {
+        self.keyboard_input.get_just_pressed().next().is_some()
+            || self.mouse_buttons.get_just_pressed().next().is_some()
+            || self.gamepad_buttons.get_just_pressed().next().is_some()
+            || self.touches.any_just_pressed()
+    }
+}
+
+pub fn reset_attract_timer(mut attract_timer: ResMut<AttractTimer>) {
+    attract_timer.0.reset();
+}
+
+// Starts the demo once the main menu has sat idle for long enough
+pub fn start_demo_when_idle(
+    mut commands: Commands,
+    any_input: AnyInput,
+    mut attract_timer: ResMut<AttractTimer>,
+    mut player_controller: ResMut<PlayerController>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    time: Res<Time>,
+) {
+    if any_input.just_pressed() {
+        attract_timer.0.reset();
+        return;
+    }
+    attract_timer.0.tick(time.delta());
+    if attract_timer.0.just_finished() {
+        commands.insert_resource(DemoMode);
+        *player_controller = PlayerController::Bot;
+        next_app_state.set(AppState::InGame);
+    }
+}
+
+// Any input hands the cabinet back to the player. Running out of lives does
+// too, overriding the switch to the game over screen made during the tick
+pub fn end_demo(
+    mut commands: Commands,
+    any_input: AnyInput,
+    lives: Res<Lives>,
+    mut player_controller: ResMut<PlayerController>,
+    options: Res<GameOptions>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if any_input.just_pressed() || lives.0 == 0 {
+        commands.remove_resource::<DemoMode>();
+        *player_controller = options.controller;
+        next_app_state.set(AppState::MainMenu);
+        next_game_state.set(GameState::Running);
+    }
+}
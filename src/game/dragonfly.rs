@@ -0,0 +1,211 @@
+// The dragonfly dives down the field in a zig-zag and drops mushrooms into the
+// player area, the one place the beetle leaves clear. It only appears on
+// levels that enable it
+
+use super::*;
+use crate::audio::SoundEffects;
+use rand::Rng;
+
+#[derive(Component)]
+pub struct Dragonfly {
+    // 1.0 when swerving right, -1.0 when swerving left
+    direction: f32,
+    // Where it last turned, so each swerve is the same width
+    turn_x: f32,
+}
+
+impl Dragonfly {
+    pub fn spawn(
+        starting_transform: &Transform,
+        direction: f32,
+        commands: &mut Commands,
+        game_assets: &Res<GameAssets>,
+    ) {
+        commands.spawn((
+            Dragonfly {
+                direction,
+                turn_x: starting_transform.translation.x,
+            },
+            SpriteSheetBundle {
+                texture: game_assets.dragonfly_texture.clone(),
+                transform: *starting_transform,
+                atlas: TextureAtlas {
+                    layout: game_assets.dragonfly_layout.clone(),
+                    index: 0,
+                },
+                ..default()
+            },
+            Animation::new(2, DRAGONFLY_ANIMATION_TIME),
+            Name::from("Dragonfly"),
+            Interpolated::new(starting_transform.translation),
+        ));
+    }
+
+    pub fn despawn(dragonfly_entity: Entity, commands: &mut Commands) {
+        commands.entity(dragonfly_entity).despawn();
+    }
+
+    pub fn kill(
+        dragonfly_entity: Entity,
+        commands: &mut Commands,
+        score: &mut ResMut<Score>,
+        tuning: &Tuning,
+    ) {
+        Self::despawn(dragonfly_entity, commands);
+        score.0 += tuning.dragonfly_reward;
+    }
+}
+
+pub fn spawn_dragonfly(
+    mut commands: Commands,
+    game_vars: Res<GameVariables>,
+    playfield: Res<Playfield>,
+    game_assets: Res<GameAssets>,
+    dragonfly_query: Query<(), With<Dragonfly>>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+) {
+    // Only run on levels with dragonflies
+    if !game_vars.dragonfly_enabled {
+        return;
+    }
+
+    // Only one dragonfly at a time
+    if !dragonfly_query.is_empty() {
+        return;
+    }
+
+    if !game_rng.gen_bool(tuning.dragonfly_spawn_rate) {
+        return;
+    }
+
+    // Dive from just below the HUD, with room to swerve either way
+    let x = game_rng.gen_range(DRAGONFLY_ZIGZAG_WIDTH..playfield.width - DRAGONFLY_ZIGZAG_WIDTH);
+    let direction = if game_rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let y = playfield.height - TOP_UI_HEIGHT - DRAGONFLY_SIZE / 2.0;
+
+    Dragonfly::spawn(
+        &Transform::from_xyz(x, y, 0.0),
+        direction,
+        &mut commands,
+        &game_assets,
+    );
+}
+
+pub fn move_dragonfly(
+    mut dragonfly_query: Query<(&mut Transform, &mut Dragonfly)>,
+    playfield: Res<Playfield>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    slow_motion: Res<SlowMotion>,
+) {
+    if let Ok((mut dragonfly_transform, mut dragonfly)) = dragonfly_query.get_single_mut() {
+        let step = tuning.dragonfly_speed * slow_motion.factor() * time.delta_seconds();
+        let translation = &mut dragonfly_transform.translation;
+        translation.y -= step;
+        translation.x += dragonfly.direction * step;
+
+        // Swerve back the other way after a full zig, or at a wall
+        let swerved = (translation.x - dragonfly.turn_x).abs() >= DRAGONFLY_ZIGZAG_WIDTH;
+        let at_wall = translation.x <= DRAGONFLY_SIZE / 2.0
+            || translation.x >= playfield.width - DRAGONFLY_SIZE / 2.0;
+        if swerved || at_wall {
+            translation.x = translation
+                .x
+                .clamp(DRAGONFLY_SIZE / 2.0, playfield.width - DRAGONFLY_SIZE / 2.0);
+            dragonfly.direction = -dragonfly.direction;
+            dragonfly.turn_x = translation.x;
+        }
+    }
+}
+
+pub fn despawn_dragonfly(
+    dragonfly_query: Query<(&Transform, Entity), With<Dragonfly>>,
+    mut commands: Commands,
+) {
+    if let Ok((dragonfly_transform, dragonfly_entity)) = dragonfly_query.get_single() {
+        // Despawn once it has dived off the bottom
+        if dragonfly_transform.translation.y < 0.0 {
+            Dragonfly::despawn(dragonfly_entity, &mut commands);
+        }
+    }
+}
+
+pub fn dragonfly_spawn_shroom(
+    dragonfly_query: Query<&Transform, With<Dragonfly>>,
+    mut spawn_mushroom_ew: EventWriter<SpawnMushroomEvent>,
+    sound_effects: SoundEffects,
+    mushroom_grid: Res<MushroomGrid>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+) {
+    if let Ok(dragonfly_transform) = dragonfly_query.get_single() {
+        // Only drop into the player area
+        if dragonfly_transform.translation.y >= TOP_BOUND {
+            return;
+        }
+
+        if !game_rng.gen_bool(tuning.dragonfly_drop_rate) {
+            return;
+        }
+
+        // Nothing to drop where there's already a mushroom
+        if mushroom_grid.is_occupied(MushroomGrid::cell(dragonfly_transform.translation)) {
+            return;
+        }
+
+        let x = dragonfly_transform.translation.x;
+        let y = dragonfly_transform.translation.y;
+
+        spawn_mushroom_ew.send(SpawnMushroomEvent(
+            Transform::from_xyz(x, y, 0.0),
+            MUSHROOM_FRESH_COLOR,
+        ));
+        sound_effects.play(|audio_handles| &audio_handles.spawn, SPAWN_VOLUME);
+    }
+}
+
+pub fn dragonfly_hits_player(
+    dragonfly_query: Query<&Transform, With<Dragonfly>>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut next_player_state: ResMut<NextState<PlayerState>>,
+    mut commands: Commands,
+    mut down_timer: ResMut<DownTimer>,
+    mut lives: ResMut<Lives>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    if let (Ok(dragonfly_transform), Ok((player_entity, player_transform))) =
+        (dragonfly_query.get_single(), player_query.get_single())
+    {
+        let distance = dragonfly_transform
+            .translation
+            .distance(player_transform.translation);
+        if distance > (DRAGONFLY_SIZE + PLAYER_SIZE) / 2.0 {
+            return;
+        }
+        Player::kill(
+            player_transform,
+            player_entity,
+            &mut next_player_state,
+            &mut commands,
+            &mut down_timer,
+            &mut lives,
+            &mut explosion_events,
+        )
+    }
+}
+
+pub fn animate_dragonfly(
+    mut dragonfly_query: Query<(&mut TextureAtlas, &mut Animation), With<Dragonfly>>,
+    time: Res<Time>,
+) {
+    if let Ok((mut atlas, mut animation)) = dragonfly_query.get_single_mut() {
+        animation.timer.tick(time.delta());
+        if animation.timer.finished() {
+            animation.current_frame = (animation.current_frame + 1) % animation.frames;
+            atlas.index = animation.current_frame;
+
+            animation.timer.reset();
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// The earwig crawls straight across the top half of the field, poisoning every
+// mushroom it passes over. Its lane is fixed once it appears, so players can
+// see where poison is coming and plan around it
+
+use super::*;
+use rand::Rng;
+use std::f32::consts::FRAC_PI_2;
+
+#[derive(Component)]
+pub struct Earwig {
+    // 1.0 when crawling right, -1.0 when crawling left
+    direction: f32,
+}
+
+impl Earwig {
+    pub fn spawn(
+        starting_transform: &Transform,
+        direction: f32,
+        commands: &mut Commands,
+        game_assets: &Res<GameAssets>,
+    ) {
+        commands.spawn((
+            Earwig { direction },
+            SpriteSheetBundle {
+                texture: game_assets.earwig_texture.clone(),
+                transform: *starting_transform,
+                atlas: TextureAtlas {
+                    layout: game_assets.earwig_layout.clone(),
+                    index: 0,
+                },
+                ..default()
+            },
+            Animation::new(2, EARWIG_ANIMATION_TIME),
+            Name::from("Earwig"),
+            Interpolated::new(starting_transform.translation),
+        ));
+    }
+
+    pub fn despawn(earwig_entity: Entity, commands: &mut Commands) {
+        commands.entity(earwig_entity).despawn();
+    }
+
+    pub fn kill(
+        earwig_entity: Entity,
+        commands: &mut Commands,
+        score: &mut ResMut<Score>,
+        tuning: &Tuning,
+    ) {
+        Self::despawn(earwig_entity, commands);
+        score.0 += tuning.earwig_reward;
+    }
+}
+
+pub fn spawn_earwig(
+    mut commands: Commands,
+    game_vars: Res<GameVariables>,
+    playfield: Res<Playfield>,
+    game_assets: Res<GameAssets>,
+    earwig_query: Query<(), With<Earwig>>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+) {
+    // Only run on levels with earwigs
+    if !game_vars.earwig_enabled {
+        return;
+    }
+
+    // Only one earwig at a time
+    if !earwig_query.is_empty() {
+        return;
+    }
+
+    if !game_rng.gen_bool(tuning.earwig_spawn_rate) {
+        return;
+    }
+
+    // Enter from either side, head first
+    let (x, direction) = if game_rng.gen_bool(0.5) {
+        (0.0, 1.0)
+    } else {
+        (playfield.width, -1.0)
+    };
+
+    // Crawl along a row of mushrooms in the top half
+    let height = game_rng.gen_range(playfield.height / 2.0..playfield.height - EARWIG_TOP_MARGIN);
+    let y = MushroomGrid::cell_center(MushroomGrid::cell(Vec3::new(0.0, height, 0.0))).y;
+
+    let starting_transform =
+        Transform::from_xyz(x, y, 0.0).with_rotation(Quat::from_rotation_z(-direction * FRAC_PI_2));
+    Earwig::spawn(&starting_transform, direction, &mut commands, &game_assets);
+}
+
+pub fn move_earwig(
+    mut earwig_query: Query<(&mut Transform, &Earwig)>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    slow_motion: Res<SlowMotion>,
+) {
+    if let Ok((mut earwig_transform, earwig)) = earwig_query.get_single_mut() {
+        let speed = tuning.earwig_speed * slow_motion.factor();
+        earwig_transform.translation.x += earwig.direction * speed * time.delta_seconds();
+    }
+}
+
+pub fn despawn_earwig(
+    earwig_query: Query<(&Transform, Entity), With<Earwig>>,
+    playfield: Res<Playfield>,
+    mut commands: Commands,
+) {
+    if let Ok((earwig_transform, earwig_entity)) = earwig_query.get_single() {
+        // Despawn once it has crawled off the other side
+        if earwig_transform.translation.x < 0.0 || earwig_transform.translation.x > playfield.width
+        {
+            Earwig::despawn(earwig_entity, &mut commands);
+        }
+    }
+}
+
+// Poisons every mushroom under the earwig, from its head to its pincers, so
+// none in its lane are missed
+pub fn earwig_poisons_shrooms(
+    earwig_query: Query<&Transform, With<Earwig>>,
+    mut mushroom_query: Query<&mut Mushroom>,
+    mushroom_grid: Res<MushroomGrid>,
+) {
+    for earwig_transform in earwig_query.iter() {
+        let half_length = Vec3::new(EARWIG_SIZE / 2.0, 0.0, 0.0);
+        let back = MushroomGrid::cell(earwig_transform.translation - half_length);
+        let front = MushroomGrid::cell(earwig_transform.translation + half_length);
+
+        for x in back.x.min(front.x)..=back.x.max(front.x) {
+            let Some(mushroom_entity) = mushroom_grid.get(IVec2::new(x, back.y)) else {
+                continue;
+            };
+            if let Ok(mut mushroom) = mushroom_query.get_mut(mushroom_entity) {
+                *mushroom = Mushroom::Poison;
+            }
+        }
+    }
+}
+
+pub fn animate_earwig(
+    mut earwig_query: Query<(&mut TextureAtlas, &mut Animation), With<Earwig>>,
+    time: Res<Time>,
+) {
+    if let Ok((mut atlas, mut animation)) = earwig_query.get_single_mut() {
+        animation.timer.tick(time.delta());
+        if animation.timer.finished() {
+            animation.current_frame = (animation.current_frame + 1) % animation.frames;
+            atlas.index = animation.current_frame;
+
+            animation.timer.reset();
+        }
+    }
+}
@@ -1,6 +1,5 @@
 use super::*;
-use crate::audio::AudioHandles;
-use bevy_kira_audio::{Audio, AudioControl};
+use crate::audio::SoundEffects;
 use std::time::Duration;
 
 #[derive(Component)]
@@ -13,8 +12,7 @@ pub fn spawn_explosion(
     mut commands: Commands,
     mut explosion_events: EventReader<ExplosionEvent>,
     game_assets: Res<GameAssets>,
-    audio: Res<Audio>,
-    audio_handles: Res<AudioHandles>,
+    sound_effects: SoundEffects,
 ) {
     for event in explosion_events.read() {
         let explosion_texture = &game_assets.explosion_texture;
@@ -38,7 +36,7 @@ pub fn spawn_explosion(
         ));
 
         // Play sound
-        audio.play(audio_handles.explosion.clone()).with_volume(0.4);
+        sound_effects.play(|audio_handles| &audio_handles.explosion, EXPLOSION_VOLUME);
     }
 }
 
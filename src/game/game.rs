@@ -1,9 +1,19 @@
 use super::*;
+use bevy::utils::Duration;
+use serde::Deserialize;
 
-#[derive(Resource)]
+// Difficulty of the current level. The tuning file lists one of these for
+// each level, see `Tuning::level`
+#[derive(Resource, Deserialize, Clone)]
 pub struct GameVariables {
     pub millipede_length: usize,
     pub millipede_speed: f32,
+    pub segment_spawn_timer_length: f32,
+    pub beetle_enabled: bool,
+    pub spider_enabled: bool,
+    pub scorpion_enabled: bool,
+    pub earwig_enabled: bool,
+    pub dragonfly_enabled: bool,
     pub spider_speed: f32,
     pub spider_attack_rate: f64,
     pub spider_leave_rate: f64,
@@ -13,41 +23,71 @@ pub struct GameVariables {
     pub spider_reward: u32,
 }
 
+// How each level past the end of the table gets harder than the one before
+#[derive(Deserialize, Clone)]
+pub struct LevelGrowth {
+    pub millipede_length_increase: usize,
+    pub millipede_max_length: usize,
+    pub millipede_speed_multiplier: f32,
+    pub millipede_max_speed: f32,
+    pub spider_speed_multiplier: f32,
+    pub spider_attack_rate_multiplier: f64,
+    pub spider_leave_rate_multiplier: f64,
+}
+
 impl GameVariables {
-    pub fn reset(&mut self) {
-        self.millipede_length = MILLIPEDE_STARTING_LENGTH;
-        self.millipede_speed = MILLIPEDE_SPEED;
-        self.spider_speed = SPIDER_SPEED;
-        self.spider_attack_rate = SPIDER_ATTACK_RATE;
-        self.spider_leave_rate = SPIDER_LEAVE_RATE;
-        self.spider_timer_length = SPIDER_TIMER;
-        self.spider_spawn_rate = SPIDER_SPAWN_RATE;
-        self.spider_average_spawn_height = SPIDER_AVERAGE_SPAWN_HEIGHT;
-        self.spider_reward = SPIDER_REWARD;
+    pub fn grow(&mut self, growth: &LevelGrowth) {
+        self.millipede_length = (self.millipede_length + growth.millipede_length_increase)
+            .min(growth.millipede_max_length.max(self.millipede_length));
+        self.millipede_speed = (self.millipede_speed * growth.millipede_speed_multiplier)
+            .min(growth.millipede_max_speed.max(self.millipede_speed));
+        self.spider_speed *= growth.spider_speed_multiplier;
+        self.spider_attack_rate =
+            (self.spider_attack_rate * growth.spider_attack_rate_multiplier).min(1.0);
+        self.spider_leave_rate =
+            (self.spider_leave_rate * growth.spider_leave_rate_multiplier).min(1.0);
+    }
+
+    pub fn apply_timers(
+        &self,
+        spider_timer: &mut SpiderTimer,
+        segment_spawner_timer: &mut SegmentSpawnerTimer,
+    ) {
+        spider_timer
+            .0
+            .set_duration(Duration::from_secs_f32(self.spider_timer_length));
+        segment_spawner_timer
+            .0
+            .set_duration(Duration::from_secs_f32(self.segment_spawn_timer_length));
     }
 }
 
 impl FromWorld for GameVariables {
-    fn from_world(_world: &mut World) -> Self {
-        let mut vars = GameVariables {
-            millipede_length: MILLIPEDE_STARTING_LENGTH,
-            millipede_speed: MILLIPEDE_SPEED,
-            spider_speed: SPIDER_SPEED,
-            spider_attack_rate: SPIDER_ATTACK_RATE,
-            spider_leave_rate: SPIDER_LEAVE_RATE,
-            spider_timer_length: SPIDER_TIMER,
-            spider_spawn_rate: SPIDER_SPAWN_RATE,
-            spider_average_spawn_height: SPIDER_AVERAGE_SPAWN_HEIGHT,
-            spider_reward: SPIDER_REWARD,
-        };
-        vars.reset();
-        vars
+    fn from_world(world: &mut World) -> Self {
+        world.resource::<Tuning>().level(1)
     }
 }
 
+// Starts a run with the tuned lives and the first level's difficulty
+pub fn reset_game_variables(
+    mut lives: ResMut<Lives>,
+    mut game_vars: ResMut<GameVariables>,
+    mut grid_step_progress: ResMut<GridStepProgress>,
+    tuning: Res<Tuning>,
+) {
+    lives.0 = tuning.starting_lives;
+
+    *game_vars = tuning.level(1);
+    grid_step_progress.0 = 0.0;
+}
+
+// Heads straight back into a new game once the old one has been left
+pub fn finish_restart(mut next_app_state: ResMut<NextState<AppState>>) {
+    next_app_state.set(AppState::InGame);
+}
+
 pub fn init_game(
     mut commands: Commands,
-    mut lives: ResMut<Lives>,
     mut score: ResMut<Score>,
     mut level: ResMut<Level>,
     mut down_timer: ResMut<DownTimer>,
@@ -56,22 +96,17 @@ pub fn init_game(
     mushroom_query: Query<Entity, With<Mushroom>>,
     millipede_query: Query<Entity, With<Segment>>,
     spider_query: Query<Entity, With<Spider>>,
-    scorpion_query: Query<Entity, With<Scorpion>>,
+    intruder_query: Query<Entity, Intruder>,
     beetle_query: Query<Entity, With<Beetle>>,
     explosion_query: Query<Entity, With<Explosion>>,
+    projectile_query: Query<Entity, With<PlayerProjectile>>,
     mut next_level_state: ResMut<NextState<LevelState>>,
-    mut shroom_amount: ResMut<ShroomAmount>,
-    mut game_vars: ResMut<GameVariables>,
+    mut mushroom_grid: ResMut<MushroomGrid>,
 ) {
-    lives.0 = STARTING_LIVES;
-
     score.0 = 0;
 
     level.0 = 0;
 
-    // Reset Game vars
-    game_vars.reset();
-
     down_timer.0.reset();
 
     // Depspawn existing spider
@@ -81,9 +116,9 @@ pub fn init_game(
 
     spider_timer.0.reset();
 
-    // Despawn existing scorpion
-    if let Ok(scorpion_entity) = scorpion_query.get_single() {
-        commands.entity(scorpion_entity).despawn();
+    // Despawn existing scorpion, earwig, inchworm, dragonfly and bees
+    for intruder_entity in intruder_query.iter() {
+        commands.entity(intruder_entity).despawn();
     }
 
     // Despawn existing beetle
@@ -100,7 +135,7 @@ pub fn init_game(
     for mushroom_entity in mushroom_query.iter() {
         commands.entity(mushroom_entity).despawn();
     }
-    shroom_amount.0 = 0;
+    mushroom_grid.clear();
 
     // Despawn existing millipede
     for millipede_entity in millipede_query.iter() {
@@ -111,6 +146,11 @@ pub fn init_game(
     for explosion_entity in explosion_query.iter() {
         commands.entity(explosion_entity).despawn();
     }
+
+    // Despawn a projectile still in flight, e.g. when restarting from the pause menu
+    for projectile_entity in projectile_query.iter() {
+        commands.entity(projectile_entity).despawn();
+    }
     // Init level state
     next_level_state.set(LevelState::Changing);
 }
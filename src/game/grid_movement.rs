@@ -0,0 +1,198 @@
+use super::*;
+use bevy::{ecs::system::SystemParam, utils::HashSet};
+use serde::Deserialize;
+
+// How millipedes get around, picked with `millipede_movement` in the tuning file
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum MillipedeMovement {
+    // Heads glide freely and get nudged back when they bump into things
+    #[default]
+    Free,
+    // Like the arcade game, heads step from cell to cell and drop one row at
+    // each obstacle, with the body stepping through the same cells
+    Grid,
+}
+
+pub fn free_movement(tuning: Res<Tuning>) -> bool {
+    tuning.millipede_movement == MillipedeMovement::Free
+}
+
+pub fn grid_movement(tuning: Res<Tuning>) -> bool {
+    tuning.millipede_movement == MillipedeMovement::Grid
+}
+
+// The step a segment is taking, from the middle of one mushroom cell to the next
+#[derive(Component)]
+pub struct GridStep {
+    pub from: IVec2,
+    pub to: IVec2,
+}
+
+// How far every millipede is through its current step, from 0 to 1. All
+// segments step together so the body always lines up with the head
+#[derive(Resource, Default)]
+pub struct GridStepProgress(pub f32);
+
+// Moves the step progress along with time, slowed down by the inchworm
+#[derive(SystemParam)]
+pub struct GridStepClock<'w> {
+    progress: ResMut<'w, GridStepProgress>,
+    slow_motion: Res<'w, SlowMotion>,
+    time: Res<'w, Time>,
+}
+
+impl GridStepClock<'_> {
+    // Advances at `speed` pixels a second, returning true when a step finishes
+    fn advance(&mut self, speed: f32) -> bool {
+        let speed = speed * self.slow_motion.factor();
+        self.progress.0 += speed * self.time.delta_seconds() / MUSHROOM_CELL_SIZE;
+        if self.progress.0 < 1.0 {
+            return false;
+        }
+        self.progress.0 = self.progress.0.fract();
+        true
+    }
+}
+
+// Snaps new segments to the middle of their cell
+pub fn start_grid_steps(
+    mut commands: Commands,
+    mut segment_query: Query<(Entity, &mut Transform, Has<GridStep>), With<Segment>>,
+) {
+    for (entity, mut transform, has_grid_step) in segment_query.iter_mut() {
+        if has_grid_step {
+            continue;
+        }
+        let cell = MushroomGrid::cell(transform.translation);
+        transform.translation = MushroomGrid::cell_center(cell);
+        commands.entity(entity).insert(GridStep {
+            from: cell,
+            to: cell,
+        });
+    }
+}
+
+pub fn grid_segment_movement(
+    mut segment_query: Query<(
+        Entity,
+        &mut Segment,
+        &ChainLink,
+        &mut GridStep,
+        &mut Transform,
+        &mut Animation,
+    )>,
+    mushroom_query: Query<&Mushroom>,
+    mushroom_grid: Res<MushroomGrid>,
+    mut clock: GridStepClock,
+    game_vars: Res<GameVariables>,
+    playfield: Res<Playfield>,
+) {
+    if clock.advance(game_vars.millipede_speed) {
+        // Every segment has reached its cell, so work out the next ones
+        let heads: Vec<Entity> = segment_query
+            .iter()
+            .filter(|(_, segment, _, _, _, _)| matches!(**segment, Segment::Head { .. }))
+            .map(|(entity, _, _, _, _, _)| entity)
+            .collect();
+        // Heads treat each other as obstacles, so they turn instead of overlapping
+        let mut head_cells: HashSet<IVec2> = heads
+            .iter()
+            .filter_map(|entity| segment_query.get(*entity).ok())
+            .map(|(_, _, _, step, _, _)| step.to)
+            .collect();
+
+        let columns = MushroomGrid::cell(Vec3::new(playfield.width, 0.0, 0.0)).x;
+        let player_area_row = MushroomGrid::cell(Vec3::new(0.0, TOP_BOUND, 0.0)).y;
+
+        // Walk each millipede from the head, handing each segment the cell its
+        // parent just reached
+        for head_entity in heads {
+            let mut next_entity = Some(head_entity);
+            let mut parent_cell = None;
+            while let Some(entity) = next_entity {
+                let Ok((_, mut segment, link, mut step, mut transform, _)) =
+                    segment_query.get_mut(entity)
+                else {
+                    break;
+                };
+                next_entity = link.child;
+
+                let cell = step.to;
+                let next = match &mut *segment {
+                    // Follow into the cell the parent just reached
+                    Segment::Body => parent_cell.unwrap_or(cell),
+                    Segment::Head {
+                        direction,
+                        head_state,
+                    } => {
+                        if *head_state == HeadState::Poisoned && cell.y > 0 {
+                            // Dive straight down
+                            cell - IVec2::Y
+                        } else {
+                            // Head back up at the bottom, and back down at the top of
+                            // the player area
+                            if cell.y <= 0 {
+                                direction.y = 1.0;
+                            }
+                            if cell.y >= player_area_row && direction.y > 0.0 {
+                                direction.y = -1.0;
+                            }
+
+                            let ahead = cell + IVec2::new(direction.x as i32, 0);
+                            let mushroom = mushroom_grid.get(ahead);
+                            if let Some(Ok(Mushroom::Poison)) =
+                                mushroom.map(|entity| mushroom_query.get(entity))
+                            {
+                                *head_state = HeadState::Poisoned;
+                            }
+
+                            let blocked = ahead.x < 0
+                                || ahead.x >= columns
+                                || mushroom.is_some()
+                                || head_cells.contains(&ahead);
+                            if blocked {
+                                // Turn around and drop exactly one row
+                                direction.x = -direction.x;
+                                cell + IVec2::new(0, direction.y as i32)
+                            } else {
+                                ahead
+                            }
+                        }
+                    }
+                };
+
+                if let Segment::Head { .. } = *segment {
+                    head_cells.insert(next);
+                }
+
+                // Face the way it's stepping, heads are drawn the other way round
+                if next != cell {
+                    let heading = (next - cell).as_vec2();
+                    let angle = heading.y.atan2(heading.x);
+                    let turn = match *segment {
+                        Segment::Head { .. } => -std::f32::consts::FRAC_PI_2,
+                        Segment::Body => std::f32::consts::FRAC_PI_2,
+                    };
+                    transform.rotation = Quat::from_rotation_z(angle + turn);
+                }
+
+                step.from = cell;
+                step.to = next;
+                parent_cell = Some(cell);
+            }
+        }
+    }
+
+    for (_, segment, _, step, mut transform, mut animation) in segment_query.iter_mut() {
+        let from = MushroomGrid::cell_center(step.from);
+        let to = MushroomGrid::cell_center(step.to);
+        transform.translation = from.lerp(to, clock.progress.0);
+
+        // Heads are always animated, see `animate_segments`
+        if let Segment::Body = *segment {
+            if step.from != step.to {
+                animation.timer.tick(clock.time.delta());
+            }
+        }
+    }
+}
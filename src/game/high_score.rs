@@ -0,0 +1,62 @@
+use super::*;
+
+const HIGH_SCORES_KEY: &str = "high_scores";
+
+#[derive(Clone, PartialEq)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: u32,
+}
+
+// Best scores so far, highest first. Saved as one "AAA 1234" line per entry
+#[derive(Resource, Default)]
+pub struct HighScores(pub Vec<HighScore>);
+
+impl HighScores {
+    pub fn load() -> Self {
+        let mut high_scores = HighScores::default();
+        if let Some(saved) = crate::storage::load(HIGH_SCORES_KEY) {
+            for line in saved.lines() {
+                // Skip anything that doesn't look like an entry
+                if let Some((initials, score)) = line.split_once(' ') {
+                    if let Ok(score) = score.trim().parse() {
+                        high_scores.insert(initials.to_string(), score);
+                    }
+                }
+            }
+        }
+        high_scores
+    }
+
+    pub fn save(&self) {
+        let saved: String = self
+            .0
+            .iter()
+            .map(|entry| format!("{} {}\n", entry.initials, entry.score))
+            .collect();
+        if let Err(error) = crate::storage::save(HIGH_SCORES_KEY, &saved) {
+            warn!("Could not save high scores: {}", error);
+        }
+    }
+
+    pub fn best(&self) -> u32 {
+        self.0.first().map_or(0, |entry| entry.score)
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.0.len() < HIGH_SCORE_COUNT
+                || self.0.last().is_some_and(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, initials: String, score: u32) {
+        // Earlier entries keep their place on ties
+        let rank = self
+            .0
+            .iter()
+            .take_while(|entry| entry.score >= score)
+            .count();
+        self.0.insert(rank, HighScore { initials, score });
+        self.0.truncate(HIGH_SCORE_COUNT);
+    }
+}
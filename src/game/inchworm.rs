@@ -0,0 +1,189 @@
+// The inchworm crawls slowly through the mushroom field. It never hurts the
+// player, but shooting it slows every enemy down for a while
+
+use super::*;
+use bevy::utils::Duration;
+use rand::Rng;
+
+#[derive(Component)]
+pub struct Inchworm {
+    // 1.0 when crawling right, -1.0 when crawling left
+    direction: f32,
+}
+
+impl Inchworm {
+    pub fn spawn(
+        starting_transform: &Transform,
+        direction: f32,
+        commands: &mut Commands,
+        game_assets: &Res<GameAssets>,
+    ) {
+        commands.spawn((
+            Inchworm { direction },
+            SpriteSheetBundle {
+                texture: game_assets.inchworm_texture.clone(),
+                transform: *starting_transform,
+                atlas: TextureAtlas {
+                    layout: game_assets.inchworm_layout.clone(),
+                    index: 0,
+                },
+                // The sprite faces right
+                sprite: Sprite {
+                    flip_x: direction < 0.0,
+                    ..default()
+                },
+                ..default()
+            },
+            Animation::new(2, INCHWORM_ANIMATION_TIME),
+            Name::from("Inchworm"),
+            Interpolated::new(starting_transform.translation),
+        ));
+    }
+
+    pub fn despawn(inchworm_entity: Entity, commands: &mut Commands) {
+        commands.entity(inchworm_entity).despawn();
+    }
+
+    pub fn kill(
+        inchworm_entity: Entity,
+        commands: &mut Commands,
+        score: &mut ResMut<Score>,
+        slow_motion: &mut SlowMotion,
+        tuning: &Tuning,
+    ) {
+        Self::despawn(inchworm_entity, commands);
+        score.0 += tuning.inchworm_reward;
+        slow_motion.start(tuning);
+    }
+}
+
+// Enemies move at a fraction of their speed until the timer runs out
+#[derive(Resource, Default)]
+pub struct SlowMotion {
+    timer: Option<Timer>,
+    factor: f32,
+}
+
+impl SlowMotion {
+    pub fn start(&mut self, tuning: &Tuning) {
+        self.timer = Some(Timer::from_seconds(
+            tuning.slow_motion_duration,
+            TimerMode::Once,
+        ));
+        self.factor = tuning.slow_motion_factor;
+    }
+
+    pub fn stop(&mut self) {
+        self.timer = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.timer.is_some()
+    }
+
+    // Multiplies every enemy's speed
+    pub fn factor(&self) -> f32 {
+        if self.is_active() {
+            self.factor
+        } else {
+            1.0
+        }
+    }
+
+    pub fn remaining_secs(&self) -> f32 {
+        self.timer.as_ref().map_or(0.0, Timer::remaining_secs)
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        if let Some(timer) = &mut self.timer {
+            if timer.tick(delta).finished() {
+                self.timer = None;
+            }
+        }
+    }
+}
+
+pub fn spawn_inchworm(
+    mut commands: Commands,
+    playfield: Res<Playfield>,
+    game_assets: Res<GameAssets>,
+    inchworm_query: Query<(), With<Inchworm>>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+) {
+    // Only one inchworm at a time
+    if !inchworm_query.is_empty() {
+        return;
+    }
+
+    if !game_rng.gen_bool(tuning.inchworm_spawn_rate) {
+        return;
+    }
+
+    // Enter from either side
+    let (x, direction) = if game_rng.gen_bool(0.5) {
+        (0.0, 1.0)
+    } else {
+        (playfield.width, -1.0)
+    };
+
+    // Crawl along a row of mushrooms above the player area
+    let height = game_rng.gen_range(TOP_BOUND..playfield.height - TOP_UI_HEIGHT - INCHWORM_SIZE);
+    let y = MushroomGrid::cell_center(MushroomGrid::cell(Vec3::new(0.0, height, 0.0))).y;
+
+    Inchworm::spawn(
+        &Transform::from_xyz(x, y, 0.0),
+        direction,
+        &mut commands,
+        &game_assets,
+    );
+}
+
+pub fn move_inchworm(
+    mut inchworm_query: Query<(&mut Transform, &Inchworm)>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+) {
+    if let Ok((mut inchworm_transform, inchworm)) = inchworm_query.get_single_mut() {
+        inchworm_transform.translation.x +=
+            inchworm.direction * tuning.inchworm_speed * time.delta_seconds();
+    }
+}
+
+pub fn despawn_inchworm(
+    inchworm_query: Query<(&Transform, Entity), With<Inchworm>>,
+    playfield: Res<Playfield>,
+    mut commands: Commands,
+) {
+    if let Ok((inchworm_transform, inchworm_entity)) = inchworm_query.get_single() {
+        // Despawn once it has crawled off the other side
+        if inchworm_transform.translation.x < 0.0
+            || inchworm_transform.translation.x > playfield.width
+        {
+            Inchworm::despawn(inchworm_entity, &mut commands);
+        }
+    }
+}
+
+pub fn tick_slow_motion(mut slow_motion: ResMut<SlowMotion>, time: Res<Time>) {
+    slow_motion.tick(time.delta());
+}
+
+pub fn reset_slow_motion(mut slow_motion: ResMut<SlowMotion>) {
+    slow_motion.stop();
+}
+
+pub fn animate_inchworm(
+    mut inchworm_query: Query<(&mut TextureAtlas, &mut Animation), With<Inchworm>>,
+    time: Res<Time>,
+) {
+    if let Ok((mut atlas, mut animation)) = inchworm_query.get_single_mut() {
+        animation.timer.tick(time.delta());
+        if animation.timer.finished() {
+            animation.current_frame = (animation.current_frame + 1) % animation.frames;
+            atlas.index = animation.current_frame;
+
+            animation.timer.reset();
+        }
+    }
+}
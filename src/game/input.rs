@@ -0,0 +1,155 @@
+use super::*;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+
+// What the player wants to do this tick. Gameplay reads this instead of the
+// keyboard so inputs can also come from a replay
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub struct PlayerInput {
+    pub movement: Vec2,
+    pub fire: bool,
+}
+
+// Where `PlayerInput` comes from. Gameplay doesn't care, so the player can be
+// swapped for a replay or the bot without touching movement or shooting
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayerController {
+    #[default]
+    Local,
+    Replay,
+    Bot,
+    // Whatever embeds the game writes `PlayerInput` itself, e.g. `MillipedeEnv`
+    External,
+}
+
+// Run condition for the systems feeding `PlayerInput`
+pub fn controlled_by(controller: PlayerController) -> impl Fn(Res<PlayerController>) -> bool {
+    move |current: Res<PlayerController>| *current == controller
+}
+
+// The gamepad the player is using. It's the first one connected, or whichever
+// one last had a button pressed
+#[derive(Resource, Default)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+impl ActiveGamepad {
+    pub fn pressed(&self, buttons: &ButtonInput<GamepadButton>, button: GamepadButtonType) -> bool {
+        self.0
+            .is_some_and(|gamepad| buttons.pressed(GamepadButton::new(gamepad, button)))
+    }
+
+    pub fn just_pressed(
+        &self,
+        buttons: &ButtonInput<GamepadButton>,
+        button: GamepadButtonType,
+    ) -> bool {
+        self.0
+            .is_some_and(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button)))
+    }
+
+    pub fn stick(&self, axes: &Axis<GamepadAxis>) -> Vec2 {
+        let Some(gamepad) = self.0 else {
+            return Vec2::ZERO;
+        };
+        let axis = |axis_type| {
+            axes.get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or(0.0)
+        };
+        Vec2::new(
+            axis(GamepadAxisType::LeftStickX),
+            axis(GamepadAxisType::LeftStickY),
+        )
+    }
+}
+
+pub fn track_gamepads(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    mut active_gamepad: ResMut<ActiveGamepad>,
+) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                if active_gamepad.0.is_none() {
+                    info!("Using gamepad {}", info.name);
+                    active_gamepad.0 = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                if active_gamepad.0 != Some(event.gamepad) {
+                    continue;
+                }
+                // Fall back to another gamepad if there is one
+                active_gamepad.0 = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+            }
+        }
+    }
+
+    // Switch to whichever gamepad is being used
+    if let Some(button) = gamepad_buttons.get_just_pressed().next() {
+        active_gamepad.0 = Some(button.gamepad);
+    }
+}
+
+// Pause so the player doesn't die while reconnecting. Runs before
+// `track_gamepads` moves on to another gamepad
+pub fn pause_on_gamepad_loss(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    active_gamepad: Res<ActiveGamepad>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let lost_active = connection_events.read().any(|event| {
+        event.connection == GamepadConnection::Disconnected
+            && active_gamepad.0 == Some(event.gamepad)
+    });
+    if lost_active {
+        next_game_state.set(GameState::Paused);
+    }
+}
+
+pub fn toggle_pause(
+    actions: ActionInput,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Menu) {
+        match game_state.get() {
+            GameState::Running => next_game_state.set(GameState::Paused),
+            GameState::Paused => next_game_state.set(GameState::Running),
+        }
+    }
+}
+
+// No ticks run while paused. Otherwise each one would still age the events, and
+// anything sent on the last tick before the pause would be dropped unread
+pub fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+pub fn read_local_input(actions: ActionInput, mut player_input: ResMut<PlayerInput>) {
+    let mut movement = Vec2::ZERO;
+    if actions.pressed(Action::MoveLeft) {
+        movement.x -= 1.0;
+    }
+    if actions.pressed(Action::MoveRight) {
+        movement.x += 1.0;
+    }
+    if actions.pressed(Action::MoveUp) {
+        movement.y += 1.0;
+    }
+    if actions.pressed(Action::MoveDown) {
+        movement.y -= 1.0;
+    }
+
+    // The stick moves the player in proportion to how far it's pushed
+    movement += actions.stick();
+
+    *player_input = PlayerInput {
+        movement: movement.clamp(Vec2::NEG_ONE, Vec2::ONE),
+        fire: actions.pressed(Action::Fire),
+    };
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+// Gameplay is simulated on a fixed tick, so moving sprites are drawn between
+// their last two simulated positions to stay smooth at any frame rate
+#[derive(Component)]
+pub struct Interpolated {
+    previous: Vec3,
+    current: Vec3,
+}
+
+impl Interpolated {
+    pub fn new(translation: Vec3) -> Self {
+        Interpolated {
+            previous: translation,
+            current: translation,
+        }
+    }
+
+    // Where the last tick left the entity, rather than where it's drawn
+    pub fn simulated_translation(&self) -> Vec3 {
+        self.current
+    }
+}
+
+// Put back the simulated position before a tick runs
+pub fn restore_simulated_translation(mut query: Query<(&mut Transform, &mut Interpolated)>) {
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        transform.translation = interpolated.current;
+        interpolated.previous = interpolated.current;
+    }
+}
+
+// Remember where the tick left each entity
+pub fn store_simulated_translation(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.current = transform.translation;
+    }
+}
+
+pub fn interpolate_translation(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &Interpolated)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
+    }
+}
@@ -14,12 +14,20 @@ pub fn check_if_change_level(
     segment_query: Query<(), With<Segment>>,
     mut next_level_state: ResMut<NextState<LevelState>>,
     mut down_timer: ResMut<DownTimer>,
+    level: Res<Level>,
+    tuning: Res<Tuning>,
 ) {
     // Only run if all segments are dead
     if !segment_query.is_empty() {
         return;
     }
 
+    // Some levels are followed by a swarm of bees before the next millipede
+    if SwarmWave::follows_level(level.0, &tuning) {
+        next_level_state.set(LevelState::Swarm);
+        return;
+    }
+
     // Start the down timer
     down_timer.0.reset();
 
@@ -32,13 +40,15 @@ pub fn start_new_level(
     time: Res<Time>,
     mut commands: Commands,
     game_assets: Res<GameAssets>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     mut next_level_state: ResMut<NextState<LevelState>>,
     mut level: ResMut<Level>,
     mut game_vars: ResMut<GameVariables>,
+    tuning: Res<Tuning>,
     mut segment_spawner_timer: ResMut<SegmentSpawnerTimer>,
     mut next_player_state: ResMut<NextState<PlayerState>>,
     player_q: Query<(), With<Player>>,
+    mut spider_timer: ResMut<SpiderTimer>,
 ) {
     // Wait until the downtime is over
     timer.0.tick(time.delta());
@@ -46,38 +56,13 @@ pub fn start_new_level(
         return;
     }
 
-    let window = window_query.get_single().unwrap();
-
-    let x = window.width() / 2.0;
-    let y = window.height() - TOP_UI_HEIGHT;
+    let x = playfield.width / 2.0;
+    let y = playfield.height - TOP_UI_HEIGHT;
 
     let starting_transform = Transform::from_xyz(x, y, 0.0);
 
-    // Set millipede
-    if game_vars.millipede_length < MILLIPEDE_MAX_LENGTH {
-        game_vars.millipede_length += 1;
-    }
-
-    if game_vars.millipede_speed * MILLIPEDE_SPEED_CHANGE_RATE <= MILLIPEDE_MAX_SPEED {
-        game_vars.millipede_speed *= MILLIPEDE_SPEED_CHANGE_RATE;
-    }
-
-    // Set Spider
-    game_vars.spider_speed *= 1.001;
-    game_vars.spider_attack_rate *= 1.001;
-    game_vars.spider_leave_rate /= 1.01;
-
-    if level.0 <= 5 {
-        game_vars.spider_timer_length = SPIDER_TIMER;
-    } else if level.0 <= 10 {
-        game_vars.spider_timer_length = SPIDER_TIMER - 4.0;
-        game_vars.spider_reward = SPIDER_REWARD * 2;
-        game_vars.spider_average_spawn_height = SPIDER_AVERAGE_SPAWN_HEIGHT - 150.0;
-    } else {
-        game_vars.spider_timer_length = SPIDER_TIMER - 8.0;
-        game_vars.spider_reward = SPIDER_REWARD * 4;
-        game_vars.spider_average_spawn_height = SPIDER_AVERAGE_SPAWN_HEIGHT - 250.0;
-    }
+    *game_vars = tuning.level(level.0 + 1);
+    game_vars.apply_timers(&mut spider_timer, &mut segment_spawner_timer);
 
     // Spawn new milipede
     Millipede::spawn(
@@ -114,11 +99,11 @@ pub fn restart_level_from_death(
     mut timer: ResMut<DownTimer>,
     time: Res<Time>,
     game_vars: Res<GameVariables>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     game_assets: Res<GameAssets>,
     segment_query: Query<Entity, With<Segment>>,
     spider_query: Query<Entity, With<Spider>>,
-    scorpion_query: Query<Entity, With<Scorpion>>,
+    intruder_query: Query<Entity, Intruder>,
     projectile_query: Query<Entity, With<PlayerProjectile>>,
     spider_timer: ResMut<SpiderTimer>,
     mut segment_spawner_timer: ResMut<SegmentSpawnerTimer>,
@@ -143,9 +128,9 @@ pub fn restart_level_from_death(
         Spider::despawn(spider_entity, &mut commands, spider_timer)
     }
 
-    // Despawn scorpion
-    if let Ok(scorpion_entity) = scorpion_query.get_single() {
-        Scorpion::despawn(scorpion_entity, &mut commands);
+    // Despawn scorpion, earwig, inchworm, dragonfly and bees
+    for intruder_entity in intruder_query.iter() {
+        commands.entity(intruder_entity).despawn();
     }
 
     // Despawn projectile
@@ -157,10 +142,8 @@ pub fn restart_level_from_death(
     segment_spawner_timer.0.reset();
 
     // Spawn millipede
-    let window = window_query.get_single().unwrap();
-
-    let x = window.width() / 2.0;
-    let y = window.height() - TOP_UI_HEIGHT;
+    let x = playfield.width / 2.0;
+    let y = playfield.height - TOP_UI_HEIGHT;
 
     let starting_transform = Transform::from_xyz(x, y, 0.0);
 
@@ -172,7 +155,7 @@ pub fn restart_level_from_death(
     );
 
     // Spawn the player
-    let starting_transform = Transform::from_xyz(window.width() / 2.0, PLAYER_SPAWN_Y, 0.0);
+    let starting_transform = Transform::from_xyz(playfield.width / 2.0, PLAYER_SPAWN_Y, 0.0);
     Player::spawn(
         &starting_transform,
         &mut commands,
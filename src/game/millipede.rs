@@ -14,31 +14,36 @@ pub enum Segment {
         direction: Vec3,
         head_state: HeadState,
     },
-    Body {
-        parent: Option<Entity>,
-    },
+    Body,
+}
+
+// A millipede is a chain of segments from its head to its tail. Each segment
+// links to its neighbours, so splitting a millipede only touches the segments
+// either side of the split
+#[derive(Component, Clone, Copy, Default)]
+pub struct ChainLink {
+    pub parent: Option<Entity>,
+    pub child: Option<Entity>,
 }
 
 #[derive(Event)]
 pub struct DespawnSegment {
-    pub entity: Entity,
     pub direction: Option<Vec3>,
+    pub link: ChainLink,
 }
 
 #[derive(Resource)]
 pub struct SegmentSpawnerTimer(pub Timer);
 
-impl Default for SegmentSpawnerTimer {
-    fn default() -> Self {
-        let mut timer = Timer::from_seconds(SEGMENT_SPAWN_TIMER_DURATION, TimerMode::Once);
+impl FromWorld for SegmentSpawnerTimer {
+    fn from_world(world: &mut World) -> Self {
+        let game_vars = world.resource::<Tuning>().level(1);
+        let mut timer = Timer::from_seconds(game_vars.segment_spawn_timer_length, TimerMode::Once);
         timer.pause();
         SegmentSpawnerTimer(timer)
     }
 }
 
-#[derive(Resource)]
-pub struct SegmentPositions(pub HashMap<Entity, Vec3>); // Pos, Vec
-
 pub struct Millipede;
 
 impl Millipede {
@@ -49,38 +54,39 @@ impl Millipede {
         game_assets: &Res<GameAssets>,
     ) {
         let millipede_texture = &game_assets.segment_texture;
-        let mut parent_entity: Option<Entity> = Some(
-            commands
-                .spawn((
-                    SpriteSheetBundle {
-                        texture: millipede_texture.clone(),
-                        transform: *starting_transform,
-                        atlas: TextureAtlas {
-                            layout: game_assets.segment_layout.clone(),
-                            index: 0,
-                        },
-                        ..default()
-                    },
-                    Name::from("MillipedeSegment"),
-                    Segment::Head {
-                        direction: Vec3::new(1.0, -1.0, 0.0),
-                        head_state: HeadState::Healthy,
+        let mut segments = vec![commands
+            .spawn((
+                SpriteSheetBundle {
+                    texture: millipede_texture.clone(),
+                    transform: *starting_transform,
+                    atlas: TextureAtlas {
+                        layout: game_assets.segment_layout.clone(),
+                        index: 0,
                     },
-                    Animation::new(3, SEGMENT_ANIMATION_SPEED),
-                ))
-                .id(),
-        );
+                    ..default()
+                },
+                Name::from("MillipedeSegment"),
+                Segment::Head {
+                    direction: Vec3::new(1.0, -1.0, 0.0),
+                    head_state: HeadState::Healthy,
+                },
+                Animation::new(3, SEGMENT_ANIMATION_SPEED),
+                Interpolated::new(starting_transform.translation),
+                Trail::default(),
+            ))
+            .id()];
 
         for _ in 1..length {
+            let body_transform = Transform::from_xyz(
+                starting_transform.translation.x,
+                starting_transform.translation.y + 10.0,
+                0.0,
+            );
             let entity: Entity = commands
                 .spawn((
                     SpriteSheetBundle {
                         texture: millipede_texture.clone(),
-                        transform: Transform::from_xyz(
-                            starting_transform.translation.x,
-                            starting_transform.translation.y + 10.0,
-                            0.0,
-                        ),
+                        transform: body_transform,
                         atlas: TextureAtlas {
                             layout: game_assets.segment_layout.clone(),
                             index: 0,
@@ -88,13 +94,21 @@ impl Millipede {
                         ..default()
                     },
                     Name::from("MillipedeSegment"),
-                    Segment::Body {
-                        parent: parent_entity,
-                    },
+                    Segment::Body,
                     Animation::new(3, SEGMENT_ANIMATION_SPEED),
+                    Interpolated::new(body_transform.translation),
+                    Trail::default(),
                 ))
                 .id();
-            parent_entity = Some(entity);
+            segments.push(entity);
+        }
+
+        // Link the segments up from head to tail
+        for (index, segment_entity) in segments.iter().enumerate() {
+            commands.entity(*segment_entity).insert(ChainLink {
+                parent: index.checked_sub(1).map(|parent| segments[parent]),
+                child: segments.get(index + 1).copied(),
+            });
         }
     }
 
@@ -106,15 +120,6 @@ impl Millipede {
     }
 }
 
-pub fn update_positions(
-    mut segment_positions: ResMut<SegmentPositions>,
-    query: Query<(Entity, &Transform)>,
-) {
-    for (entity, transform) in query.iter() {
-        segment_positions.0.insert(entity, transform.translation);
-    }
-}
-
 pub fn update_head_color(mut segment_query: Query<(&Segment, &mut Sprite)>) {
     for (segment, mut sprite) in segment_query.iter_mut() {
         match segment {
@@ -140,48 +145,17 @@ pub fn update_head_color(mut segment_query: Query<(&Segment, &mut Sprite)>) {
     }
 }
 
-// Stores the position in a global hashmap in order for the
-// children segments to know the position of their parents
+// Moves the heads, the body segments follow their trails, see `follow_trails`
 pub fn segment_movement(
-    segment_positions: Res<SegmentPositions>,
-    mut query: Query<(&Segment, &mut Transform, &mut Animation)>,
+    mut query: Query<(&Segment, &mut Transform)>,
     game_vars: Res<GameVariables>,
+    slow_motion: Res<SlowMotion>,
     time: Res<Time>,
 ) {
-    for (segment, mut transform, mut animation) in query.iter_mut() {
+    let speed = game_vars.millipede_speed * slow_motion.factor();
+    for (segment, mut transform) in query.iter_mut() {
         match segment {
-            Segment::Body { parent } => {
-                if let Some(parent_entity) = parent {
-                    if let Some(&parent_position) = segment_positions.0.get(parent_entity) {
-                        let distance_to_parent = transform.translation.distance(parent_position);
-                        if distance_to_parent > SEGMENT_SPACING {
-                            let previous = transform.translation;
-                            let direction_to_parent =
-                                (parent_position - transform.translation).normalize();
-                            transform.translation += direction_to_parent
-                                * game_vars.millipede_speed
-                                * 3.0
-                                * time.delta_seconds();
-
-                            // Ensure that the segment doesn't move too close to its parent
-                            if transform.translation.distance(parent_position) < SEGMENT_SPACING {
-                                transform.translation =
-                                    parent_position - direction_to_parent * SEGMENT_SPACING;
-                            }
-
-                            // Set the rotation to face the parent segment
-                            let angle = direction_to_parent.y.atan2(direction_to_parent.x);
-                            transform.rotation =
-                                Quat::from_rotation_z(angle + std::f32::consts::FRAC_PI_2);
-
-                            // Animate if moved
-                            if transform.translation.distance(previous) > 1.0 {
-                                animation.timer.tick(time.delta());
-                            }
-                        }
-                    }
-                }
-            }
+            Segment::Body => {}
             Segment::Head {
                 direction,
                 head_state,
@@ -189,8 +163,7 @@ pub fn segment_movement(
                 match head_state {
                     HeadState::Healthy => {
                         // Move in its direction
-                        transform.translation.x +=
-                            direction.x * time.delta_seconds() * game_vars.millipede_speed;
+                        transform.translation.x += direction.x * time.delta_seconds() * speed;
 
                         // Point the head in the direction it's heading
                         let target_angle = if direction.x > 0.0 {
@@ -211,7 +184,7 @@ pub fn segment_movement(
                     }
                     HeadState::Poisoned => {
                         // Move down
-                        transform.translation.y -= time.delta_seconds() * game_vars.millipede_speed;
+                        transform.translation.y -= time.delta_seconds() * speed;
 
                         // Point the head downwards
                         let target_angle = -3.1;
@@ -229,10 +202,8 @@ pub fn segment_movement(
 
 pub fn change_direction(
     mut head_query: Query<(&mut Segment, &mut Transform)>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
 ) {
-    // Get window for size
-    let window = window_query.get_single().unwrap();
     let segment_radius = SEGMENT_SIZE / 2.0;
     for (mut segment, mut transform) in head_query.iter_mut() {
         if let Segment::Head {
@@ -259,7 +230,7 @@ pub fn change_direction(
             }
 
             // And right
-            if transform.translation.x > window.width() - segment_radius {
+            if transform.translation.x > playfield.width - segment_radius {
                 direction.x = -1.0;
                 transform.translation.y += DROP_AMOUNT * direction.y;
             }
@@ -269,40 +240,46 @@ pub fn change_direction(
 
 pub fn confine_segment_movement(
     mut head_query: Query<&mut Transform, With<Segment>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
 ) {
-    let window = window_query.get_single().unwrap();
     for mut transform in head_query.iter_mut() {
         if transform.translation.y < 0.0 {
             transform.translation.y = 0.0;
         }
-        if transform.translation.y > window.height() {
-            transform.translation.y = window.height();
+        if transform.translation.y > playfield.height {
+            transform.translation.y = playfield.height;
         }
         if transform.translation.x < 0.0 {
             transform.translation.x = 0.0;
         }
-        if transform.translation.x > window.width() {
-            transform.translation.x = window.width();
+        if transform.translation.x > playfield.width {
+            transform.translation.x = playfield.width;
         }
     }
 }
 
 pub fn update_segment_parents(
     mut event_reader: EventReader<DespawnSegment>,
-    mut segment_query: Query<&mut Segment>,
+    mut segment_query: Query<(&mut Segment, &mut ChainLink)>,
 ) {
     for despawn_event in event_reader.read() {
-        for mut segment in segment_query.iter_mut() {
-            if let Segment::Body { parent } = *segment {
-                if parent == Some(despawn_event.entity) {
-                    *segment = Segment::Head {
-                        direction: despawn_event
-                            .direction
-                            .unwrap_or_else(|| Vec3::new(1.0, -1.0, 0.0)),
-                        head_state: HeadState::Healthy,
-                    };
-                }
+        // The segment in front becomes the tail
+        if let Some(parent_entity) = despawn_event.link.parent {
+            if let Ok((_, mut link)) = segment_query.get_mut(parent_entity) {
+                link.child = None;
+            }
+        }
+
+        // And the one behind becomes the head of a new millipede
+        if let Some(child_entity) = despawn_event.link.child {
+            if let Ok((mut segment, mut link)) = segment_query.get_mut(child_entity) {
+                link.parent = None;
+                *segment = Segment::Head {
+                    direction: despawn_event
+                        .direction
+                        .unwrap_or_else(|| Vec3::new(1.0, -1.0, 0.0)),
+                    head_state: HeadState::Healthy,
+                };
             }
         }
     }
@@ -311,6 +288,7 @@ pub fn update_segment_parents(
 pub fn collide_with_shroom(
     mut segment_query: Query<(&mut Transform, &mut Segment), Without<Mushroom>>,
     shroom_query: Query<&Transform, With<Mushroom>>,
+    mushroom_grid: Res<MushroomGrid>,
 ) {
     let shroom_radius = MUSHROOM_SIZE / 3.0;
     let segment_radius = SEGMENT_SIZE / 3.0;
@@ -323,7 +301,14 @@ pub fn collide_with_shroom(
             if head_state == HeadState::Poisoned {
                 return;
             }
-            for shroom_transform in shroom_query.iter() {
+            let nearby_shrooms = mushroom_grid.near(
+                segment_transform.translation,
+                shroom_radius + segment_radius,
+            );
+            for shroom_entity in nearby_shrooms {
+                let Ok(shroom_transform) = shroom_query.get(shroom_entity) else {
+                    continue;
+                };
                 let distance = shroom_transform
                     .translation
                     .distance(segment_transform.translation);
@@ -366,6 +351,8 @@ pub fn segment_hits_player(
                     &mut lives,
                     &mut explosion_events,
                 );
+                // Only die once, even when touching several segments
+                return;
             }
         }
     }
@@ -378,8 +365,8 @@ pub fn spawn_lone_head(
     game_assets: Res<GameAssets>,
     level_state: Res<State<LevelState>>,
 ) {
-    // Only run if level not changing
-    if let LevelState::Changing = level_state.get() {
+    // Only run during a millipede round
+    if *level_state.get() != LevelState::Unchanging {
         return;
     }
 
@@ -412,8 +399,14 @@ pub fn start_segment_spawner_timer(
 }
 
 // If a head collides with another head whilst on the same Y value, change their directions
-pub fn collide_with_head(mut segment_query: Query<(Entity, &mut Transform, &mut Segment)>) {
+pub fn collide_with_head(
+    mut segment_query: Query<(Entity, &mut Transform, &mut Segment)>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+    spatial_grid: Res<SpatialGrid>,
+) {
     let mut heads = Vec::new();
+    let mut head_indices = HashMap::new();
 
     // Collect entities and their positions if they are heads
     for (entity, transform, segment) in segment_query.iter_mut() {
@@ -422,6 +415,7 @@ pub fn collide_with_head(mut segment_query: Query<(Entity, &mut Transform, &mut
             head_state: _,
         } = &*segment
         {
+            head_indices.insert(entity, heads.len());
             heads.push((entity, transform.translation, *direction));
         }
     }
@@ -434,10 +428,15 @@ pub fn collide_with_head(mut segment_query: Query<(Entity, &mut Transform, &mut
     // Collect changes to apply later
     let mut changes = Vec::new();
 
-    // Compare each position
-    for i in 0..heads.len() {
-        for j in i + 1..heads.len() {
-            let (entity1, pos1, _) = heads[i];
+    // Compare each position with the heads nearby, counting every pair once
+    for (i, &(entity1, pos1, _)) in heads.iter().enumerate() {
+        for other_entity in spatial_grid.segments_near(pos1, SEGMENT_SIZE / 1.3) {
+            let Some(&j) = head_indices.get(&other_entity) else {
+                continue;
+            };
+            if j <= i {
+                continue;
+            }
             let (entity2, pos2, _) = heads[j];
 
             if (pos1.y - pos2.y).abs() <= SEGMENT_SIZE / 1.3
@@ -460,13 +459,13 @@ pub fn collide_with_head(mut segment_query: Query<(Entity, &mut Transform, &mut
             {
                 direction.x = -direction.x;
                 // Bounce backwards slightly
-                let pushback = rand::thread_rng().gen_range(0..10);
+                let pushback = game_rng.gen_range(0..10);
                 transform.translation.x += direction.x * PUSH_BACK_AMOUNT + (pushback as f32);
 
                 // Randomly decide to drop
                 // It needs to randomly drop in order to remove the chance that it gets caught in a
                 // loop
-                let drop = rand::thread_rng().gen_bool(SEGMENT_DROP_RATE);
+                let drop = game_rng.gen_bool(tuning.segment_drop_rate);
                 if drop {
                     transform.translation.y += DROP_AMOUNT * direction.y;
                 }
@@ -478,6 +477,7 @@ pub fn collide_with_head(mut segment_query: Query<(Entity, &mut Transform, &mut
 pub fn head_gets_poisoned(
     mut segment_query: Query<(&mut Segment, &Transform)>,
     mushroom_query: Query<(&Mushroom, &Transform)>,
+    mushroom_grid: Res<MushroomGrid>,
 ) {
     // If a non-poisoned head touches a poison mushroom, the head
     // becomes poisoned
@@ -492,7 +492,14 @@ pub fn head_gets_poisoned(
                 match *head_state {
                     HeadState::Healthy => {
                         // Check if touching shroom
-                        for (mushroom, mushroom_transform) in mushroom_query.iter() {
+                        let nearby_shrooms = mushroom_grid
+                            .near(transform.translation, shroom_radius + segment_radius);
+                        for mushroom_entity in nearby_shrooms {
+                            let Ok((mushroom, mushroom_transform)) =
+                                mushroom_query.get(mushroom_entity)
+                            else {
+                                continue;
+                            };
                             if *mushroom != Mushroom::Poison {
                                 continue;
                             }
@@ -1,52 +1,182 @@
-use crate::{AppState, GameState};
-use bevy::{prelude::*, utils::HashMap, window::PrimaryWindow};
+use crate::{AppState, GameState, OptionsState};
+use bevy::{prelude::*, transform::TransformSystem, utils::HashMap};
 
-pub struct GamePlugin;
+// The binary fills the options in from its command line. Whatever embeds the
+// game as a library, e.g. `MillipedeEnv`, gives them itself instead, so the
+// host's own arguments are left alone
+pub struct GamePlugin {
+    pub options: GameOptions,
+}
+
+#[derive(Resource, Clone)]
+pub struct GameOptions {
+    // None picks a fresh random seed for every run
+    pub seed: Option<u64>,
+    pub tuning: String,
+    pub tick_rate: f64,
+    pub controller: PlayerController,
+    // Played back instead of reading the keyboard, with its own seed and tuning
+    pub replay: Option<Replay>,
+    // Where each run is saved once it's over, if anywhere
+    pub record: Option<String>,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        GameOptions {
+            seed: None,
+            tuning: String::from("default"),
+            tick_rate: FIXED_TICK_RATE,
+            controller: PlayerController::Local,
+            replay: None,
+            record: None,
+        }
+    }
+}
 
+impl GameOptions {
+    // Reads the options from the command line, exiting if any can't be used
+    pub fn from_args() -> Self {
+        GameOptions {
+            seed: crate::args::value("--seed"),
+            tuning: crate::args::value("--tuning").unwrap_or(String::from("default")),
+            tick_rate: tick_rate_from_args(),
+            // `--autoplay` hands the game to the bot from the start
+            controller: if crate::args::flag("--autoplay") {
+                PlayerController::Bot
+            } else {
+                PlayerController::Local
+            },
+            replay: Replay::from_args(),
+            record: Some(crate::args::value("--record").unwrap_or(String::from("last_run.replay"))),
+        }
+    }
+}
+
+pub mod actions;
 pub mod assets;
 pub mod beetle;
+pub mod bot;
+pub mod demo;
+pub mod dragonfly;
+pub mod earwig;
 pub mod explosion;
 pub mod game;
+pub mod grid_movement;
+pub mod high_score;
+pub mod inchworm;
+pub mod input;
+pub mod interpolation;
 pub mod level;
 pub mod millipede;
 pub mod player;
+pub mod playfield;
 pub mod projectile;
+pub mod replay;
+pub mod rng;
 pub mod scorpion;
 pub mod shroom;
+pub mod spatial_grid;
 pub mod spider;
+pub mod swarm;
+pub mod touch;
+pub mod trackball;
+pub mod trail;
+pub mod tuning;
 
 use crate::{constants::*, ui::*};
+use actions::*;
 use assets::*;
 use beetle::*;
+use bot::*;
+use demo::*;
+use dragonfly::*;
+use earwig::*;
 use explosion::*;
 use game::*;
+use grid_movement::*;
+use high_score::*;
+use inchworm::*;
+use input::*;
+use interpolation::*;
 use level::*;
 use millipede::*;
 use player::*;
+use playfield::*;
 use projectile::*;
+use replay::*;
+use rng::*;
 use scorpion::*;
 use shroom::*;
+use spatial_grid::*;
 use spider::*;
+use swarm::*;
+use touch::*;
+use trackball::*;
+use trail::*;
+use tuning::*;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
+        let options = self.options.clone();
+
+        // A replay plays with the tuning it was recorded with
+        let tuning_name = options
+            .replay
+            .as_ref()
+            .map_or(&options.tuning, |replay| &replay.tuning);
+        let tuning = load_tuning(app, tuning_name);
+
         app.add_systems(
             OnEnter(AppState::InGame),
-            (init_game, spawn_player, spawn_shroom_field).chain(),
+            (
+                seed_game_rng,
+                start_recording,
+                reset_game_variables,
+                reset_trackball,
+                reset_slow_motion,
+                init_game,
+                spawn_player,
+                spawn_shroom_field,
+            )
+                .chain(),
         )
         .add_systems(
             OnExit(AppState::GameOver),
             (despawn_player, despawn_shroom_field),
         )
+        .add_systems(OnEnter(AppState::Restarting), finish_restart)
         .add_systems(
-            Update,
+            FixedUpdate,
+            (
+                (read_local_input, read_trackball_input, read_touch_input)
+                    .chain()
+                    .run_if(controlled_by(PlayerController::Local)),
+                drive_bot.run_if(controlled_by(PlayerController::Bot)),
+                play_back_input.run_if(controlled_by(PlayerController::Replay)),
+                record_input,
+            )
+                .chain()
+                .in_set(InputSet)
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::Running)),
+        )
+        // Every tick runs its systems in a fixed order, otherwise systems sharing the
+        // rng or transforms could run in a different order and replays would diverge
+        .add_systems(
+            FixedUpdate,
             (
                 (
                     (move_player, shoot_projectile, confine_player_movement)
+                        .chain()
                         .in_set(GameplaySet::Player),
                     (
                         move_projectile,
                         projectile_hits_scorpion,
+                        projectile_hits_earwig,
+                        projectile_hits_inchworm,
+                        projectile_hits_dragonfly,
+                        projectile_hits_bee,
                         projectile_hits_spider,
                         projectile_hits_segment,
                         projectile_hits_beetle,
@@ -54,6 +184,7 @@ impl Plugin for GamePlugin {
                         despawn_projectile,
                         despawn_mushroom,
                     )
+                        .chain()
                         .in_set(GameplaySet::Projectile),
                     (
                         (
@@ -61,28 +192,40 @@ impl Plugin for GamePlugin {
                             move_beetle,
                             beetle_spawn_shroom,
                             despawn_beetle,
-                        ),
+                        )
+                            .chain(),
                         (
                             (
                                 update_segment_parents,
-                                update_positions,
-                                segment_movement,
+                                (segment_movement, follow_trails)
+                                    .chain()
+                                    .run_if(free_movement),
+                                (start_grid_steps, grid_segment_movement)
+                                    .chain()
+                                    .run_if(grid_movement),
                                 confine_segment_movement,
+                                update_spatial_grid,
                                 segment_hits_player,
                             ).chain(),
-                            change_direction,
+                            change_direction.run_if(free_movement),
                             head_gets_poisoned,
-                            collide_with_shroom,
+                            collide_with_shroom.run_if(free_movement),
                             start_segment_spawner_timer,
                             spawn_lone_head,
-                            collide_with_head,
+                            update_spatial_grid,
+                            collide_with_head.run_if(free_movement),
                             update_shroom_color,
                             update_head_color,
                             update_shroom_sprite,
                             animate_spider,
                             animate_segments,
                             animate_scorpion,
-                        ),
+                            animate_earwig,
+                            animate_inchworm,
+                            animate_dragonfly,
+                            animate_bees,
+                        )
+                            .chain(),
                         (
                             spawn_spider,
                             set_spider_direction,
@@ -92,12 +235,36 @@ impl Plugin for GamePlugin {
                             spider_hits_player,
                             spider_eats_shroom,
                             convert_to_poison_shroom,
-                        ),
-                        (spawn_scorpion, move_scorpion, despawn_scorpion),
+                        )
+                            .chain(),
+                        (spawn_scorpion, move_scorpion, despawn_scorpion).chain(),
+                        (
+                            spawn_earwig,
+                            move_earwig,
+                            despawn_earwig,
+                            earwig_poisons_shrooms,
+                        )
+                            .chain(),
+                        (
+                            tick_slow_motion,
+                            spawn_inchworm,
+                            move_inchworm,
+                            despawn_inchworm,
+                        )
+                            .chain(),
+                        (
+                            spawn_dragonfly,
+                            move_dragonfly,
+                            despawn_dragonfly,
+                            dragonfly_spawn_shroom,
+                            dragonfly_hits_player,
+                        )
+                            .chain(),
+                        (move_bees, despawn_bees, bee_hits_player).chain(),
                     )
+                        .chain()
                         .in_set(GameplaySet::Enemies),
                 )
-                    .run_if(in_state(GameState::Running))
                     .run_if(in_state(PlayerState::Alive)),
                 (restart_level_from_death,)
                     .run_if(in_state(PlayerState::Dead))
@@ -107,12 +274,27 @@ impl Plugin for GamePlugin {
                         .chain()
                         .run_if(in_state(LevelState::Changing)),
                     (check_if_change_level).run_if(in_state(LevelState::Unchanging)),
-                ),
+                    end_swarm_wave.run_if(in_state(LevelState::Swarm)),
+                    tally_swarm_wave.run_if(in_state(LevelState::Tally)),
+                )
+                    .chain(),
                 (heal_shrooms,).run_if(in_state(PlayerState::Dead)),
+            )
+                .chain()
+                // Nothing in a round moves on while paused, including the timers
+                // between lives and levels
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::Running)),
+        )
+        .add_systems(
+            Update,
+            (
                 ((
                     update_level_ui,
                     update_lives_ui,
                     update_score_ui,
+                    update_high_score_ui,
+                    update_slow_motion_ui,
                     spawn_explosion,
                     despawn_explosions,
                 )),
@@ -120,10 +302,60 @@ impl Plugin for GamePlugin {
             )
                 .run_if(in_state(AppState::InGame)),
         )
-        .add_systems(Update, score_event)
-        .insert_resource(SegmentPositions(HashMap::new()))
-        .insert_resource(ShroomAmount(0))
-        .insert_resource(Lives(STARTING_LIVES))
+        .add_systems(Update, (score_event, grab_cursor))
+        .add_systems(OnEnter(GameState::Paused), pause_time)
+        .add_systems(OnExit(GameState::Paused), resume_time)
+        // Attract mode
+        .add_systems(OnEnter(AppState::MainMenu), reset_attract_timer)
+        .add_systems(
+            Update,
+            (
+                start_demo_when_idle
+                    .run_if(in_state(AppState::MainMenu))
+                    .run_if(in_state(OptionsState::Closed))
+                    .run_if(controlled_by(PlayerController::Local)),
+                end_demo
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(resource_exists::<DemoMode>),
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                // Only a player at the controls needs the game held for them
+                pause_on_gamepad_loss
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(controlled_by(PlayerController::Local))
+                    .run_if(not(resource_exists::<DemoMode>)),
+                track_gamepads,
+                toggle_pause
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(OptionsState::Closed))
+                    .run_if(not(resource_exists::<DemoMode>)),
+            )
+                .chain(),
+        )
+        // Apply player and level changes between ticks rather than between frames,
+        // so the simulation doesn't depend on the frame rate
+        .add_systems(
+            FixedPostUpdate,
+            (
+                apply_state_transition::<PlayerState>,
+                apply_state_transition::<LevelState>,
+            )
+                .chain(),
+        )
+        .add_systems(OnEnter(LevelState::Swarm), start_swarm_wave)
+        .add_systems(FixedFirst, restore_simulated_translation)
+        .add_systems(FixedLast, store_simulated_translation)
+        .add_systems(PostUpdate, remove_from_spatial_grid)
+        .add_systems(
+            PostUpdate,
+            interpolate_translation.before(TransformSystem::TransformPropagate),
+        )
+        .insert_resource(Time::<Fixed>::from_hz(options.tick_rate))
+        .init_resource::<MushroomGrid>()
+        .insert_resource(Lives(tuning.starting_lives))
         .insert_resource(Score(0))
         .insert_resource(Level(0))
         .insert_resource(DownTimer(Timer::from_seconds(DOWNTIMER, TimerMode::Once)))
@@ -132,15 +364,35 @@ impl Plugin for GamePlugin {
             TimerMode::Once,
         )))
         .insert_resource(SpiderTimer(Timer::from_seconds(
-            SPIDER_TIMER,
+            tuning.level(1).spider_timer_length,
             TimerMode::Once,
         )))
+        .insert_resource(tuning)
         .init_resource::<GameAssets>()
+        .init_resource::<Playfield>()
         .init_resource::<GameVariables>()
+        .init_resource::<GameRng>()
+        .insert_resource(options.clone())
+        .insert_resource(GameSeed(options.seed))
+        .insert_resource(HighScores::load())
+        .init_resource::<PlayerInput>()
+        .insert_resource(options.controller)
+        .init_resource::<ActiveGamepad>()
+        .insert_resource(Bindings::load())
+        .insert_resource(TrackballSettings::load())
+        .init_resource::<Trackball>()
+        .init_resource::<TouchDrag>()
+        .init_resource::<AttractTimer>()
+        .init_resource::<SlowMotion>()
+        .init_resource::<SwarmWave>()
+        .init_resource::<ReplayRecorder>()
         .init_resource::<SegmentSpawnerTimer>()
+        .init_resource::<GridStepProgress>()
+        .init_resource::<SpatialGrid>()
         .configure_sets(
-            Update,
+            FixedUpdate,
             (
+                InputSet.before(GameplaySet::Player),
                 GameplaySet::Player.before(GameplaySet::Projectile),
                 GameplaySet::Projectile.before(GameplaySet::Enemies),
                 GameplaySet::Enemies,
@@ -152,7 +404,66 @@ impl Plugin for GamePlugin {
         .add_event::<FloatingScoreEvent>()
         .init_state::<LevelState>()
         .init_state::<PlayerState>();
+
+        // Save every run so it can be shared or replayed
+        #[cfg(not(target_arch = "wasm32"))]
+        if options.record.is_some() {
+            app.add_systems(OnEnter(AppState::GameOver), save_recording);
+        }
+
+        // Let the bot play game after game
+        if options.controller == PlayerController::Bot {
+            app.add_systems(Startup, start_autoplay)
+                .add_systems(OnEnter(AppState::GameOver), restart_autoplay);
+        }
+
+        // Play back a recorded run instead of reading the keyboard
+        if let Some(replay) = options.replay.clone() {
+            app.insert_resource(PlayerController::Replay)
+                .insert_resource(GameSeed(Some(replay.seed)))
+                .insert_resource(Time::<Fixed>::from_duration(replay.timestep))
+                .insert_resource(ReplayPlayback::new(replay))
+                .add_systems(Startup, start_replay);
+        }
+    }
+}
+
+// Reads `--tick-rate <hz>`, exiting if it can't be used
+fn tick_rate_from_args() -> f64 {
+    let Some(tick_rate) = crate::args::value::<f64>("--tick-rate") else {
+        return FIXED_TICK_RATE;
+    };
+    if !tick_rate.is_finite() || tick_rate <= 0.0 {
+        eprintln!("Could not use tick rate {}: it must be above 0", tick_rate);
+        std::process::exit(1);
     }
+    tick_rate
+}
+
+// Starts loading the tuning file and returns the tuning to begin with. Without
+// an asset server (when headless) the file is read once and never reloaded
+fn load_tuning(app: &mut App, tuning_name: &str) -> Tuning {
+    app.insert_resource(TuningName(tuning_name.to_string()));
+
+    if !app.world.contains_resource::<AssetServer>() {
+        if tuning_name == "default" {
+            return Tuning::default();
+        }
+        return Tuning::load_file(tuning_name).unwrap_or_else(|error| {
+            eprintln!("Could not load tuning {}: {}", tuning_name, error);
+            std::process::exit(1);
+        });
+    }
+
+    app.init_asset::<Tuning>()
+        .register_asset_loader(TuningLoader)
+        .add_systems(PreUpdate, (apply_tuning, wait_for_tuning).chain());
+    let tuning_handle = app
+        .world
+        .resource::<AssetServer>()
+        .load(Tuning::path(tuning_name));
+    app.insert_resource(TuningHandle::new(tuning_handle));
+    Tuning::default()
 }
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
@@ -170,6 +481,10 @@ pub enum LevelState {
     #[default]
     Changing,
     Unchanging,
+    // A bonus wave of bees between millipede rounds
+    Swarm,
+    // The wave's results on screen
+    Tally,
 }
 
 #[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
@@ -182,5 +497,14 @@ pub enum PlayerState {
 #[derive(Resource)]
 pub struct Score(pub u32);
 
+// The enemies that pass through the field once, cleared away together
+pub type Intruder = Or<(
+    With<Scorpion>,
+    With<Earwig>,
+    With<Inchworm>,
+    With<Dragonfly>,
+    With<Bee>,
+)>;
+
 #[derive(Component)]
 pub struct Health(pub i8);
@@ -22,6 +22,7 @@ impl Player {
             },
             Name::from("Player"),
             Player,
+            Interpolated::new(location_transform.translation),
         ));
 
         // Set player state
@@ -59,12 +60,10 @@ pub struct Lives(pub u8);
 pub fn spawn_player(
     mut commands: Commands,
     game_assets: Res<GameAssets>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     mut next_player_state: ResMut<NextState<PlayerState>>,
 ) {
-    let window = window_query.get_single().unwrap();
-
-    let starting_transform = Transform::from_xyz(window.width() / 2.0, PLAYER_SPAWN_Y, 0.0);
+    let starting_transform = Transform::from_xyz(playfield.width / 2.0, PLAYER_SPAWN_Y, 0.0);
 
     Player::spawn(
         &starting_transform,
@@ -82,35 +81,24 @@ pub fn despawn_player(mut commands: Commands, player_query: Query<Entity, With<P
 
 pub fn move_player(
     mut player_query: Query<&mut Transform, With<Player>>,
-    input: Res<ButtonInput<KeyCode>>,
+    player_input: Res<PlayerInput>,
     time: Res<Time>,
+    tuning: Res<Tuning>,
 ) {
     if let Ok(mut player_transform) = player_query.get_single_mut() {
-        if input.pressed(LEFT) {
-            player_transform.translation.x -= PLAYER_SPEED * time.delta_seconds();
-        }
-        if input.pressed(RIGHT) {
-            player_transform.translation.x += PLAYER_SPEED * time.delta_seconds();
-        }
-        if input.pressed(UP) {
-            player_transform.translation.y += PLAYER_SPEED * time.delta_seconds();
-        }
-        if input.pressed(DOWN) {
-            player_transform.translation.y -= PLAYER_SPEED * time.delta_seconds();
-        }
+        let movement = player_input.movement * tuning.player_speed * time.delta_seconds();
+        player_transform.translation += movement.extend(0.0);
     }
 }
 
 pub fn confine_player_movement(
     mut player_query: Query<&mut Transform, With<Player>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
 ) {
     if let Ok(mut player_transform) = player_query.get_single_mut() {
-        let window = window_query.get_single().unwrap();
-
         let half_player_size = PLAYER_SIZE / 2.0;
         let x_min = 0.0 + half_player_size;
-        let x_max = window.width() - half_player_size;
+        let x_max = playfield.width - half_player_size;
         let y_min = 0.0 + half_player_size;
         let y_max = TOP_BOUND - half_player_size;
 
@@ -0,0 +1,18 @@
+use super::*;
+
+// The size of the arena. Gameplay reads this instead of the window so the
+// simulation can also run without one
+#[derive(Resource, Clone, Copy)]
+pub struct Playfield {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Playfield {
+            width: PLAYFIELD_WIDTH,
+            height: PLAYFIELD_HEIGHT,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::*;
-use crate::audio::AudioHandles;
-use bevy_kira_audio::{Audio, AudioControl};
+use crate::audio::SoundEffects;
+use bevy::ecs::system::SystemParam;
 
 #[derive(Component)]
 pub struct PlayerProjectile;
@@ -20,54 +20,62 @@ impl PlayerProjectile {
                 transform: *location_transform,
                 ..default()
             },
+            Interpolated::new(location_transform.translation),
         ));
     }
 }
 
+// What a shot can leave behind where it hits: an explosion, the points it was
+// worth floating up and a mushroom
+#[derive(SystemParam)]
+pub struct HitEffects<'w> {
+    explosions: EventWriter<'w, ExplosionEvent>,
+    floating_scores: EventWriter<'w, FloatingScoreEvent>,
+    mushrooms: EventWriter<'w, SpawnMushroomEvent>,
+}
+
 pub fn shoot_projectile(
     mut commands: Commands,
     player_query: Query<&Transform, With<Player>>,
     projectile_query: Query<Entity, With<PlayerProjectile>>,
-    input: Res<ButtonInput<KeyCode>>,
+    player_input: Res<PlayerInput>,
     game_assets: Res<GameAssets>,
-    audio: Res<Audio>,
-    audio_handles: Res<AudioHandles>,
+    sound_effects: SoundEffects,
 ) {
     // Check if a projectile already exists
     if !projectile_query.is_empty() {
         return;
     }
 
-    // Check if shoot key is pressed
-    if !(input.pressed(SHOOT_KEY)) {
+    // Check if the player is firing
+    if !player_input.fire {
         return;
     }
     if let Ok(player_transform) = player_query.get_single() {
         PlayerProjectile::spawn(&player_transform, &mut commands, &game_assets);
         // Play shoot sound
-        audio.play(audio_handles.shoot.clone()).with_volume(0.2);
+        sound_effects.play(|audio_handles| &audio_handles.shoot, SHOOT_VOLUME);
     }
 }
 
 pub fn move_projectile(
     mut projectile_query: Query<&mut Transform, With<PlayerProjectile>>,
     time: Res<Time>,
+    tuning: Res<Tuning>,
 ) {
     for mut transform in projectile_query.iter_mut() {
         // Move upwards
         transform.translation.y +=
-            PROJECTILE_SPEED * PROJECTILE_ACCELERATION * time.delta_seconds();
+            tuning.projectile_speed * tuning.projectile_acceleration * time.delta_seconds();
     }
 }
 
 pub fn despawn_projectile(
     mut commands: Commands,
     projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
 ) {
-    // Get window height
-    let window = window_query.get_single().unwrap();
-    let max_height = window.height() - PLAYER_SIZE;
+    let max_height = playfield.height - PLAYER_SIZE;
     for (entity, transform) in projectile_query.iter() {
         // Check if hit ceiling
         if transform.translation.y >= max_height {
@@ -82,19 +90,29 @@ pub fn projectile_hits_shroom(
     projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
     mut mushroom_query: Query<(&mut Health, &Transform), With<Mushroom>>,
     mut score: ResMut<Score>,
+    tuning: Res<Tuning>,
+    mushroom_grid: Res<MushroomGrid>,
 ) {
     if let Ok((projectile_entity, projectile_transform)) = projectile_query.get_single() {
-        for (mut mushroom_health, mushroom_transform) in mushroom_query.iter_mut() {
-            let projectile_radius = PROJECTILE_SIZE / 2.0;
-            let mushroom_radius = MUSHROOM_SIZE / 2.0;
-
+        let projectile_radius = PROJECTILE_SIZE / 2.0;
+        let mushroom_radius = MUSHROOM_SIZE / 2.0;
+        let nearby_shrooms = mushroom_grid.near(
+            projectile_transform.translation,
+            projectile_radius + mushroom_radius,
+        );
+        for mushroom_entity in nearby_shrooms {
+            let Ok((mut mushroom_health, mushroom_transform)) =
+                mushroom_query.get_mut(mushroom_entity)
+            else {
+                continue;
+            };
             let distance = projectile_transform
                 .translation
                 .distance(mushroom_transform.translation);
             if distance < projectile_radius + mushroom_radius {
                 commands.entity(projectile_entity).despawn();
                 if mushroom_health.0 - 1 == 0 {
-                    score.0 += MUSHROOM_REWARD;
+                    score.0 += tuning.mushroom_reward;
                 }
                 mushroom_health.0 -= 1;
                 break;
@@ -106,14 +124,14 @@ pub fn projectile_hits_shroom(
 pub fn projectile_hits_segment(
     mut commands: Commands,
     projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
-    segment_query: Query<(Entity, &Transform, &Segment)>,
+    segment_query: Query<(Entity, &Transform, &Segment, &ChainLink)>,
     mut event_writer: EventWriter<DespawnSegment>,
     mut score: ResMut<Score>,
-    mut explosion_events: EventWriter<ExplosionEvent>,
-    mut spawn_mushroom_ew: EventWriter<SpawnMushroomEvent>,
+    mut hit_effects: HitEffects,
+    tuning: Res<Tuning>,
 ) {
     if let Ok((projectile_entity, projectile_transform)) = projectile_query.get_single() {
-        for (segment_entity, segment_transform, segment) in segment_query.iter() {
+        for (segment_entity, segment_transform, segment, link) in segment_query.iter() {
             let projectile_radius = PROJECTILE_SIZE / 2.0;
             let segment_radius = SEGMENT_SIZE / 2.0;
 
@@ -128,21 +146,23 @@ pub fn projectile_hits_segment(
                 } = segment
                 {
                     event_writer.send(DespawnSegment {
-                        entity: segment_entity,
                         direction: Some(*direction),
+                        link: *link,
                     });
                 } else {
                     event_writer.send(DespawnSegment {
-                        entity: segment_entity,
                         direction: None,
+                        link: *link,
                     });
                 }
 
                 // Spawn explosion
-                explosion_events.send(ExplosionEvent(segment_transform.clone()));
+                hit_effects
+                    .explosions
+                    .send(ExplosionEvent(segment_transform.clone()));
 
                 // Spawn mushroom
-                spawn_mushroom_ew.send(SpawnMushroomEvent(
+                hit_effects.mushrooms.send(SpawnMushroomEvent(
                     segment_transform.clone(),
                     Color::rgb(1.0, 1.0, 1.0),
                 ));
@@ -156,10 +176,10 @@ pub fn projectile_hits_segment(
                         direction: _,
                         head_state: _,
                     } => {
-                        score.0 += HEAD_REWARD;
+                        score.0 += tuning.head_reward;
                     }
-                    Segment::Body { parent: _ } => {
-                        score.0 += SEGMENT_REWARD;
+                    Segment::Body => {
+                        score.0 += tuning.segment_reward;
                     }
                 }
 
@@ -174,9 +194,8 @@ pub fn projectile_hits_beetle(
     projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
     beetle_query: Query<(Entity, &Transform), With<Beetle>>,
     mut score: ResMut<Score>,
-    mut explosion_events: EventWriter<ExplosionEvent>,
-    mut spawn_mushroom_ew: EventWriter<SpawnMushroomEvent>,
-    mut score_event: EventWriter<FloatingScoreEvent>,
+    mut hit_effects: HitEffects,
+    tuning: Res<Tuning>,
 ) {
     if let Ok((projectile_entity, projectile_transform)) = projectile_query.get_single() {
         for (beetle_entity, beetle_transform) in beetle_query.iter() {
@@ -188,20 +207,25 @@ pub fn projectile_hits_beetle(
                 .distance(beetle_transform.translation);
             if distance < projectile_radius + segment_radius {
                 // Spawn explosion
-                explosion_events.send(ExplosionEvent(beetle_transform.clone()));
+                hit_effects
+                    .explosions
+                    .send(ExplosionEvent(beetle_transform.clone()));
                 // Spawn mushroom
-                spawn_mushroom_ew.send(SpawnMushroomEvent(
+                hit_effects.mushrooms.send(SpawnMushroomEvent(
                     beetle_transform.clone(),
                     Color::rgb(1.0, 1.0, 1.0),
                 ));
                 // Send scoreUI event
-                score_event.send(FloatingScoreEvent(beetle_transform.clone(), BEETLE_REWARD));
+                hit_effects.floating_scores.send(FloatingScoreEvent(
+                    beetle_transform.clone(),
+                    tuning.beetle_reward,
+                ));
 
                 commands.entity(projectile_entity).despawn();
                 commands.entity(beetle_entity).despawn();
 
                 // Add to score
-                score.0 += BEETLE_REWARD;
+                score.0 += tuning.beetle_reward;
 
                 return;
             }
@@ -215,8 +239,8 @@ pub fn projectile_hits_spider(
     spider_query: Query<(Entity, &Transform), With<Spider>>,
     mut score: ResMut<Score>,
     spider_timer: ResMut<SpiderTimer>,
-    mut explosion_events: EventWriter<ExplosionEvent>,
-    mut score_event: EventWriter<FloatingScoreEvent>,
+    mut hit_effects: HitEffects,
+    game_vars: Res<GameVariables>,
 ) {
     if let Ok((projectile_entity, projectile_transform)) = projectile_query.get_single() {
         for (spider_entity, spider_transform) in spider_query.iter() {
@@ -228,12 +252,14 @@ pub fn projectile_hits_spider(
                 .distance(spider_transform.translation);
             if distance < projectile_radius + segment_radius {
                 let calculated_score =
-                    (spider_transform.translation.y * 0.8) as u32 + SPIDER_REWARD;
+                    (spider_transform.translation.y * 0.8) as u32 + game_vars.spider_reward;
 
                 // Spawn explosion
-                explosion_events.send(ExplosionEvent(spider_transform.clone()));
+                hit_effects
+                    .explosions
+                    .send(ExplosionEvent(spider_transform.clone()));
                 // Send scoreUI event
-                score_event.send(FloatingScoreEvent(
+                hit_effects.floating_scores.send(FloatingScoreEvent(
                     spider_transform.clone(),
                     calculated_score,
                 ));
@@ -258,6 +284,7 @@ pub fn projectile_hits_scorpion(
     mut score: ResMut<Score>,
     mut explosion_events: EventWriter<ExplosionEvent>,
     mut score_event: EventWriter<FloatingScoreEvent>,
+    tuning: Res<Tuning>,
 ) {
     if let (
         Ok((projectile_entity, projectile_transform)),
@@ -280,12 +307,140 @@ pub fn projectile_hits_scorpion(
         // Send scoreUI event
         score_event.send(FloatingScoreEvent(
             scorpion_transform.clone(),
-            SCORPION_REWARD,
+            tuning.scorpion_reward,
         ));
 
         //Despawn projectile
         commands.entity(projectile_entity).despawn();
         // Kill Scorpion
-        Scorpion::kill(scorpion_entity, &mut commands, &mut score);
+        Scorpion::kill(scorpion_entity, &mut commands, &mut score, &tuning);
     }
 }
+
+pub fn projectile_hits_earwig(
+    mut commands: Commands,
+    earwig_query: Query<(Entity, &Transform), With<Earwig>>,
+    projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
+    mut score: ResMut<Score>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut score_event: EventWriter<FloatingScoreEvent>,
+    tuning: Res<Tuning>,
+) {
+    if let (Ok((projectile_entity, projectile_transform)), Ok((earwig_entity, earwig_transform))) =
+        (projectile_query.get_single(), earwig_query.get_single())
+    {
+        let distance = projectile_transform
+            .translation
+            .distance(earwig_transform.translation);
+        if distance > (PROJECTILE_SIZE + EARWIG_SIZE) / 2.0 {
+            return;
+        }
+
+        explosion_events.send(ExplosionEvent(*earwig_transform));
+        score_event.send(FloatingScoreEvent(*earwig_transform, tuning.earwig_reward));
+
+        commands.entity(projectile_entity).despawn();
+        Earwig::kill(earwig_entity, &mut commands, &mut score, &tuning);
+    }
+}
+
+pub fn projectile_hits_inchworm(
+    mut commands: Commands,
+    inchworm_query: Query<(Entity, &Transform), With<Inchworm>>,
+    projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
+    mut score: ResMut<Score>,
+    mut slow_motion: ResMut<SlowMotion>,
+    mut hit_effects: HitEffects,
+    tuning: Res<Tuning>,
+) {
+    if let (
+        Ok((projectile_entity, projectile_transform)),
+        Ok((inchworm_entity, inchworm_transform)),
+    ) = (projectile_query.get_single(), inchworm_query.get_single())
+    {
+        let distance = projectile_transform
+            .translation
+            .distance(inchworm_transform.translation);
+        if distance > (PROJECTILE_SIZE + INCHWORM_SIZE) / 2.0 {
+            return;
+        }
+
+        hit_effects
+            .explosions
+            .send(ExplosionEvent(*inchworm_transform));
+        hit_effects.floating_scores.send(FloatingScoreEvent(
+            *inchworm_transform,
+            tuning.inchworm_reward,
+        ));
+
+        commands.entity(projectile_entity).despawn();
+        Inchworm::kill(
+            inchworm_entity,
+            &mut commands,
+            &mut score,
+            &mut slow_motion,
+            &tuning,
+        );
+    }
+}
+
+pub fn projectile_hits_dragonfly(
+    mut commands: Commands,
+    dragonfly_query: Query<(Entity, &Transform), With<Dragonfly>>,
+    projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
+    mut score: ResMut<Score>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut score_event: EventWriter<FloatingScoreEvent>,
+    tuning: Res<Tuning>,
+) {
+    if let (
+        Ok((projectile_entity, projectile_transform)),
+        Ok((dragonfly_entity, dragonfly_transform)),
+    ) = (projectile_query.get_single(), dragonfly_query.get_single())
+    {
+        let distance = projectile_transform
+            .translation
+            .distance(dragonfly_transform.translation);
+        if distance > (PROJECTILE_SIZE + DRAGONFLY_SIZE) / 2.0 {
+            return;
+        }
+
+        explosion_events.send(ExplosionEvent(*dragonfly_transform));
+        score_event.send(FloatingScoreEvent(
+            *dragonfly_transform,
+            tuning.dragonfly_reward,
+        ));
+
+        commands.entity(projectile_entity).despawn();
+        Dragonfly::kill(dragonfly_entity, &mut commands, &mut score, &tuning);
+    }
+}
+
+pub fn projectile_hits_bee(
+    mut commands: Commands,
+    bee_query: Query<(Entity, &Transform), With<Bee>>,
+    projectile_query: Query<(Entity, &Transform), With<PlayerProjectile>>,
+    mut score: ResMut<Score>,
+    mut swarm_wave: ResMut<SwarmWave>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut score_event: EventWriter<FloatingScoreEvent>,
+) {
+    let Ok((projectile_entity, projectile_transform)) = projectile_query.get_single() else {
+        return;
+    };
+    // The shot stops at the first bee it hits
+    let Some((bee_entity, bee_transform)) = bee_query.iter().find(|(_, bee_transform)| {
+        projectile_transform
+            .translation
+            .distance(bee_transform.translation)
+            <= (PROJECTILE_SIZE + BEE_SIZE) / 2.0
+    }) else {
+        return;
+    };
+
+    commands.entity(projectile_entity).despawn();
+    let reward = Bee::kill(bee_entity, &mut commands, &mut score, &mut swarm_wave);
+
+    explosion_events.send(ExplosionEvent(*bee_transform));
+    score_event.send(FloatingScoreEvent(*bee_transform, reward));
+}
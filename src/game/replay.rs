@@ -0,0 +1,349 @@
+// Replays store the seed and every tick of player input, which is enough to
+// reproduce a run exactly.
+//
+// File layout (little endian):
+//   magic "MLPR", format version (u8)
+//   game version length (u8) and bytes
+//   tuning name length (u8) and bytes
+//   seed (u64), tick length in nanoseconds (u64)
+//   run count (u32), then runs of (x: i8, y: i8, fire: u8, ticks: u16)
+
+use super::*;
+use std::{fmt, fs, io, time::Duration};
+
+const REPLAY_MAGIC: &[u8; 4] = b"MLPR";
+const REPLAY_FORMAT_VERSION: u8 = 2;
+const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// One tick of input, quantized so it survives the round trip through a file
+#[derive(Clone, Copy, PartialEq)]
+pub struct ReplayFrame {
+    x: i8,
+    y: i8,
+    fire: bool,
+}
+
+impl ReplayFrame {
+    pub fn from_input(input: &PlayerInput) -> Self {
+        let quantize = |axis: f32| (axis.clamp(-1.0, 1.0) * 127.0).round() as i8;
+        ReplayFrame {
+            x: quantize(input.movement.x),
+            y: quantize(input.movement.y),
+            fire: input.fire,
+        }
+    }
+
+    pub fn to_input(self) -> PlayerInput {
+        PlayerInput {
+            movement: Vec2::new(self.x as f32 / 127.0, self.y as f32 / 127.0),
+            fire: self.fire,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Replay {
+    pub seed: u64,
+    pub tuning: String,
+    pub timestep: Duration,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    NotAReplay,
+    UnsupportedFormat(u8),
+    VersionMismatch(String),
+    Truncated,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "{}", error),
+            ReplayError::NotAReplay => write!(f, "not a replay file"),
+            ReplayError::UnsupportedFormat(version) => {
+                write!(f, "unsupported replay format {}", version)
+            }
+            ReplayError::VersionMismatch(version) => write!(
+                f,
+                "recorded with version {} but this is version {}",
+                version, GAME_VERSION
+            ),
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+impl Replay {
+    pub fn load(path: &str) -> Result<Self, ReplayError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), ReplayError> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    // Loads the replay given with `--replay <file>`, exiting if it can't be used
+    pub fn from_args() -> Option<Self> {
+        let path: String = crate::args::value("--replay")?;
+        match Replay::load(&path) {
+            Ok(replay) => Some(replay),
+            Err(error) => {
+                eprintln!("Could not play {}: {}", path, error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Collapse repeated frames into runs
+        let mut runs: Vec<(ReplayFrame, u16)> = Vec::new();
+        for frame in &self.frames {
+            match runs.last_mut() {
+                Some((last, count)) if last == frame && *count < u16::MAX => *count += 1,
+                _ => runs.push((*frame, 1)),
+            }
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_FORMAT_VERSION);
+        bytes.push(GAME_VERSION.len() as u8);
+        bytes.extend_from_slice(GAME_VERSION.as_bytes());
+        bytes.push(self.tuning.len() as u8);
+        bytes.extend_from_slice(self.tuning.as_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.timestep.as_nanos() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (frame, count) in runs {
+            bytes.push(frame.x as u8);
+            bytes.push(frame.y as u8);
+            bytes.push(frame.fire as u8);
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = ByteReader(bytes);
+
+        if reader.take(4)? != REPLAY_MAGIC {
+            return Err(ReplayError::NotAReplay);
+        }
+        let format_version = reader.take(1)?[0];
+        if format_version != REPLAY_FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedFormat(format_version));
+        }
+        let version_length = reader.take(1)?[0] as usize;
+        let version = String::from_utf8_lossy(reader.take(version_length)?).to_string();
+        if version != GAME_VERSION {
+            return Err(ReplayError::VersionMismatch(version));
+        }
+        let tuning_length = reader.take(1)?[0] as usize;
+        let tuning = String::from_utf8_lossy(reader.take(tuning_length)?).to_string();
+
+        let seed = u64::from_le_bytes(reader.array()?);
+        let timestep = Duration::from_nanos(u64::from_le_bytes(reader.array()?));
+        let run_count = u32::from_le_bytes(reader.array()?);
+
+        let mut frames = Vec::new();
+        for _ in 0..run_count {
+            let [x, y, fire] = reader.array()?;
+            let count = u16::from_le_bytes(reader.array()?);
+            let frame = ReplayFrame {
+                x: x as i8,
+                y: y as i8,
+                fire: fire != 0,
+            };
+            frames.extend(std::iter::repeat_n(frame, count as usize));
+        }
+
+        Ok(Replay {
+            seed,
+            tuning,
+            timestep,
+            frames,
+        })
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], ReplayError> {
+        if self.0.len() < length {
+            return Err(ReplayError::Truncated);
+        }
+        let (taken, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
+// Inputs of the current run
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    seed: u64,
+    tuning: String,
+    frames: Vec<ReplayFrame>,
+}
+
+impl ReplayRecorder {
+    pub fn replay(&self, timestep: Duration) -> Replay {
+        Replay {
+            seed: self.seed,
+            tuning: self.tuning.clone(),
+            timestep,
+            frames: self.frames.clone(),
+        }
+    }
+}
+
+// Present while a replay is feeding the player's input
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    frames: Vec<ReplayFrame>,
+    tick: usize,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayback {
+            frames: replay.frames,
+            tick: 0,
+        }
+    }
+}
+
+pub fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    game_rng: Res<GameRng>,
+    tuning_name: Res<TuningName>,
+) {
+    recorder.seed = game_rng.seed();
+    recorder.tuning = tuning_name.0.clone();
+    recorder.frames.clear();
+}
+
+pub fn record_input(mut recorder: ResMut<ReplayRecorder>, mut player_input: ResMut<PlayerInput>) {
+    // Play with the same precision the replay will store, or playback would drift
+    let frame = ReplayFrame::from_input(&player_input);
+    *player_input = frame.to_input();
+    recorder.frames.push(frame);
+}
+
+pub fn save_recording(
+    recorder: Res<ReplayRecorder>,
+    time: Res<Time<Fixed>>,
+    options: Res<GameOptions>,
+) {
+    let Some(path) = &options.record else {
+        return;
+    };
+    let replay = recorder.replay(time.timestep());
+    if let Err(error) = replay.save(path) {
+        warn!("Could not save replay to {}: {}", path, error);
+    }
+}
+
+pub fn play_back_input(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut player_input: ResMut<PlayerInput>,
+    mut player_controller: ResMut<PlayerController>,
+) {
+    match playback.frames.get(playback.tick) {
+        Some(frame) => {
+            *player_input = frame.to_input();
+            playback.tick += 1;
+        }
+        None => {
+            // Hand control back to the player once the replay runs out
+            *player_input = PlayerInput::default();
+            *player_controller = PlayerController::Local;
+            commands.remove_resource::<ReplayPlayback>();
+        }
+    }
+}
+
+pub fn start_replay(mut next_app_state: ResMut<NextState<AppState>>) {
+    next_app_state.set(AppState::InGame);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        let held = ReplayFrame {
+            x: -127,
+            y: 64,
+            fire: true,
+        };
+        let released = ReplayFrame {
+            x: 0,
+            y: 0,
+            fire: false,
+        };
+        // Long enough that the held input needs two runs
+        let mut frames = vec![held; u16::MAX as usize + 1];
+        frames.push(released);
+        Replay {
+            seed: 42,
+            tuning: String::from("hard"),
+            timestep: Duration::from_nanos(16_666_667),
+            frames,
+        }
+    }
+
+    #[test]
+    fn round_trip_splits_long_runs() {
+        let replay = replay();
+        let bytes = replay.to_bytes();
+
+        let header = 4 + 1 + 1 + GAME_VERSION.len() + 1 + replay.tuning.len() + 8 + 8;
+        let run_count = u32::from_le_bytes(bytes[header..header + 4].try_into().unwrap());
+        assert_eq!(run_count, 3);
+        assert_eq!(bytes.len(), header + 4 + 3 * 5);
+
+        let decoded = Replay::from_bytes(&bytes).expect("replay should decode");
+        assert_eq!(decoded.seed, replay.seed);
+        assert_eq!(decoded.tuning, replay.tuning);
+        assert_eq!(decoded.timestep, replay.timestep);
+        assert!(decoded.frames == replay.frames);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = replay().to_bytes();
+        bytes[0] = b'X';
+        assert!(matches!(
+            Replay::from_bytes(&bytes),
+            Err(ReplayError::NotAReplay)
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_body() {
+        let bytes = replay().to_bytes();
+        assert!(matches!(
+            Replay::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ReplayError::Truncated)
+        ));
+    }
+}
@@ -0,0 +1,63 @@
+use super::*;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// All gameplay randomness is drawn from this resource so that a run can be
+// reproduced exactly from its seed
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        *self = GameRng::new(seed);
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng::new(rand::random())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+// The seed the next run will start with. None picks a fresh random seed for
+// every run, Some repeats the same run each time
+#[derive(Resource, Default)]
+pub struct GameSeed(pub Option<u64>);
+
+pub fn seed_game_rng(mut game_rng: ResMut<GameRng>, game_seed: Res<GameSeed>) {
+    // Pick a random seed if none was chosen
+    let seed = game_seed.0.unwrap_or_else(|| rand::thread_rng().gen());
+    game_rng.reseed(seed);
+}
@@ -33,6 +33,7 @@ impl Scorpion {
             },
             Animation::new(2, SCORPION_ANIMATION_TIME),
             Name::from("Scorpion"),
+            Interpolated::new(starting_transform.translation),
         ));
     }
 
@@ -40,21 +41,28 @@ impl Scorpion {
         commands.entity(scorpion_entity).despawn();
     }
 
-    pub fn kill(scorpion_entity: Entity, commands: &mut Commands, score: &mut ResMut<Score>) {
+    pub fn kill(
+        scorpion_entity: Entity,
+        commands: &mut Commands,
+        score: &mut ResMut<Score>,
+        tuning: &Tuning,
+    ) {
         Self::despawn(scorpion_entity, commands);
-        score.0 += SCORPION_REWARD;
+        score.0 += tuning.scorpion_reward;
     }
 }
 
 pub fn spawn_scorpion(
     mut commands: Commands,
-    level: Res<Level>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    game_vars: Res<GameVariables>,
+    playfield: Res<Playfield>,
     game_assets: Res<GameAssets>,
     scorpion_query: Query<(), With<Scorpion>>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
 ) {
-    // Only run if above a certain level
-    if level.0 < 3 {
+    // Only run on levels with scorpions
+    if !game_vars.scorpion_enabled {
         return;
     }
 
@@ -64,7 +72,7 @@ pub fn spawn_scorpion(
     }
 
     // Only spawn a certain percentage of the time
-    let spawn = rand::thread_rng().gen_bool(SCORPION_SPAWN_RATE);
+    let spawn = game_rng.gen_bool(tuning.scorpion_spawn_rate);
     if !spawn {
         return;
     }
@@ -72,43 +80,48 @@ pub fn spawn_scorpion(
     // Spawn scorpion
     let mut starting_transform: Transform = Transform::default();
 
-    let window = window_query.get_single().unwrap();
-
     // Choose either left or right
-    let left = rand::thread_rng().gen_bool(0.5);
+    let left = game_rng.gen_bool(0.5);
     if left {
         starting_transform.translation.x = 1.0;
         starting_transform.rotation = Quat::from_rotation_z(-1.55);
     } else {
-        starting_transform.translation.x = window.width() - 1.0;
+        starting_transform.translation.x = playfield.width - 1.0;
         starting_transform.rotation = Quat::from_rotation_z(1.55);
     }
 
-    // Now set height
-    let height_range = rand::thread_rng().gen_range(-75.0..75.0);
-    starting_transform.translation.y = window.height() - SCORPION_SPAWN_HEIGHT + height_range;
+    // Now set height, lined up with a row of shrooms
+    let height_range = game_rng.gen_range(-75.0..75.0);
+    let height = playfield.height - SCORPION_SPAWN_HEIGHT + height_range;
+    starting_transform.translation.y =
+        MushroomGrid::cell_center(MushroomGrid::cell(Vec3::new(0.0, height, 0.0))).y;
 
     Scorpion::spawn(&starting_transform, &mut commands, &game_assets);
 }
 
-pub fn move_scorpion(mut scorpion_query: Query<(&mut Transform, &Scorpion)>, time: Res<Time>) {
+pub fn move_scorpion(
+    mut scorpion_query: Query<(&mut Transform, &Scorpion)>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    slow_motion: Res<SlowMotion>,
+) {
     if let Ok((mut scorpion_transform, scorpion)) = scorpion_query.get_single_mut() {
-        scorpion_transform.translation.x +=
-            scorpion.direction.x * time.delta_seconds() * SCORPION_SPEED;
+        scorpion_transform.translation.x += scorpion.direction.x
+            * time.delta_seconds()
+            * tuning.scorpion_speed
+            * slow_motion.factor();
     }
 }
 
 pub fn despawn_scorpion(
     scorpion_query: Query<(&Transform, Entity), With<Scorpion>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     mut commands: Commands,
 ) {
     if let Ok((scorpion_transform, scorpion_entity)) = scorpion_query.get_single() {
-        let window = window_query.get_single().unwrap();
-
-        // Despawn if outside the window
+        // Despawn if outside the playfield
         if scorpion_transform.translation.x < 0.0
-            || scorpion_transform.translation.x > window.width()
+            || scorpion_transform.translation.x > playfield.width
         {
             Scorpion::despawn(scorpion_entity, &mut commands);
         }
@@ -117,28 +130,30 @@ pub fn despawn_scorpion(
 
 pub fn convert_to_poison_shroom(
     scorpion_query: Query<&Transform, With<Scorpion>>,
-    mut mushroom_query: Query<(&Transform, &mut Mushroom)>,
+    mut mushroom_query: Query<&mut Mushroom>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+    mushroom_grid: Res<MushroomGrid>,
 ) {
     for scorpion_transform in scorpion_query.iter() {
-        for (mushroom_transform, mut mushroom) in mushroom_query.iter_mut() {
-            // Check if scorpion collides with a shroom
-            let mushroom_radius = MUSHROOM_SIZE / 2.0;
-            let scorpion_radius = SCORPION_SIZE / 2.0;
-            let distance = scorpion_transform
-                .translation
-                .distance(mushroom_transform.translation);
-            if distance <= mushroom_radius + scorpion_radius {
-                // Randomly choose to turn it into poison mushroom
-                if *mushroom == Mushroom::Poison {
-                    return;
-                }
-                let convert = rand::thread_rng().gen_bool(MUSHROOM_CONVERSION_RATE);
-                if !convert {
-                    return;
-                }
-                *mushroom = Mushroom::Poison;
-            }
+        // Check if scorpion is over a shroom
+        let cell = MushroomGrid::cell(scorpion_transform.translation);
+        let Some(mushroom_entity) = mushroom_grid.get(cell) else {
+            continue;
+        };
+        let Ok(mut mushroom) = mushroom_query.get_mut(mushroom_entity) else {
+            continue;
+        };
+
+        // Randomly choose to turn it into poison mushroom
+        if *mushroom == Mushroom::Poison {
+            return;
+        }
+        let convert = game_rng.gen_bool(tuning.mushroom_conversion_rate);
+        if !convert {
+            return;
         }
+        *mushroom = Mushroom::Poison;
     }
 }
 
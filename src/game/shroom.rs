@@ -1,5 +1,5 @@
 use super::*;
-use rand::*;
+use rand::{seq::SliceRandom, *};
 
 use crate::constants::*;
 
@@ -9,6 +9,58 @@ pub enum Mushroom {
     Poison,
 }
 
+// Mushrooms snap to the middle of a cell the size of a segment, and a cell holds
+// at most one mushroom
+#[derive(Resource, Default)]
+pub struct MushroomGrid(HashMap<IVec2, Entity>);
+
+impl MushroomGrid {
+    pub fn cell(position: Vec3) -> IVec2 {
+        (position.truncate() / MUSHROOM_CELL_SIZE)
+            .floor()
+            .as_ivec2()
+    }
+
+    pub fn cell_center(cell: IVec2) -> Vec3 {
+        ((cell.as_vec2() + 0.5) * MUSHROOM_CELL_SIZE).extend(0.0)
+    }
+
+    pub fn get(&self, cell: IVec2) -> Option<Entity> {
+        self.0.get(&cell).copied()
+    }
+
+    // Mushrooms in the cells overlapping the square around a position, callers
+    // still check the distance
+    pub fn near(&self, position: Vec3, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let min = Self::cell(position - Vec3::splat(radius));
+        let max = Self::cell(position + Vec3::splat(radius));
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| self.get(cell))
+    }
+
+    pub fn is_occupied(&self, cell: IVec2) -> bool {
+        self.0.contains_key(&cell)
+    }
+
+    pub fn insert(&mut self, cell: IVec2, entity: Entity) {
+        self.0.insert(cell, entity);
+    }
+
+    pub fn remove(&mut self, cell: IVec2) {
+        self.0.remove(&cell);
+    }
+
+    // How many mushrooms are on the field
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
 #[derive(Event)]
 pub struct SpawnMushroomEvent(pub Transform, pub Color);
 
@@ -16,21 +68,29 @@ pub fn spawn_shroom(
     mut commands: Commands,
     mut spawn_event: EventReader<SpawnMushroomEvent>,
     game_assets: Res<GameAssets>,
-    mut shroom_amount: ResMut<ShroomAmount>,
+    mut mushroom_grid: ResMut<MushroomGrid>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
 ) {
     for event in spawn_event.read() {
+        // Only one shroom fits in a cell
+        let cell = MushroomGrid::cell(event.0.translation);
+        if mushroom_grid.is_occupied(cell) {
+            continue;
+        }
+
         // Randomly flip sprite
-        let flip = rand::thread_rng().gen_bool(0.5);
-        commands.spawn((
+        let flip = game_rng.gen_bool(0.5);
+        let mushroom_entity = commands.spawn((
             Mushroom::Normal,
-            Health(MUSHROOM_HEALTH),
+            Health(tuning.mushroom_health),
             SpriteSheetBundle {
                 texture: game_assets.shroom_texture.clone(),
                 atlas: TextureAtlas {
                     layout: game_assets.shroom_layout.clone(),
                     index: MUSHROOM_ANIMATION_INDICES.first,
                 },
-                transform: Transform::from_xyz(event.0.translation.x, event.0.translation.y, 0.0),
+                transform: Transform::from_translation(MushroomGrid::cell_center(cell)),
                 sprite: Sprite {
                     color: event.1,
                     flip_x: flip,
@@ -40,26 +100,31 @@ pub fn spawn_shroom(
             },
             Name::from("Mushroom"),
         ));
-
-        // Add to shroom count
-        shroom_amount.0 += 1;
+        mushroom_grid.insert(cell, mushroom_entity.id());
     }
 }
 
-#[derive(Resource)]
-pub struct ShroomAmount(pub u8);
-
 pub fn spawn_shroom_field(
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     mut spawn_mushroom: EventWriter<SpawnMushroomEvent>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
 ) {
-    let window = window_query.get_single().unwrap();
+    // Pick distinct cells so the field gets the full amount of shrooms
+    let min_cell = MushroomGrid::cell(Vec3::new(SPAWN_MARGIN, TOP_BOUND, 0.0));
+    let max_cell = MushroomGrid::cell(Vec3::new(
+        playfield.width - SPAWN_MARGIN,
+        playfield.height - TOP_UI_HEIGHT,
+        0.0,
+    ));
+    let cells: Vec<IVec2> = (min_cell.y..max_cell.y)
+        .flat_map(|y| (min_cell.x..max_cell.x).map(move |x| IVec2::new(x, y)))
+        .collect();
 
-    for _ in 0..MUSHROOM_MAX_AMOUNT {
-        let x = rand::thread_rng().gen_range(0.0 + SPAWN_MARGIN..window.width() - SPAWN_MARGIN);
-        let y = rand::thread_rng().gen_range(TOP_BOUND..window.height() - TOP_UI_HEIGHT);
+    let amount = tuning.mushroom_max_amount as usize;
+    for cell in cells.choose_multiple(&mut *game_rng, amount) {
         spawn_mushroom.send(SpawnMushroomEvent(
-            Transform::from_xyz(x, y, 0.0),
+            Transform::from_translation(MushroomGrid::cell_center(*cell)),
             Color::rgb(1.0, 1.0, 1.0),
         ));
     }
@@ -67,23 +132,28 @@ pub fn spawn_shroom_field(
 
 pub fn despawn_mushroom(
     mut commands: Commands,
-    shroom_q: Query<(Entity, &Health)>,
-    mut shroom_amount: ResMut<ShroomAmount>,
+    shroom_q: Query<(Entity, &Health, &Transform), With<Mushroom>>,
+    mut mushroom_grid: ResMut<MushroomGrid>,
 ) {
-    for (shroom_entity, shroom_health) in shroom_q.iter() {
+    for (shroom_entity, shroom_health, shroom_transform) in shroom_q.iter() {
         // Skip those with health
         if shroom_health.0 > 0 {
             continue;
         }
         commands.entity(shroom_entity).despawn();
-        shroom_amount.0 -= 1;
+        mushroom_grid.remove(MushroomGrid::cell(shroom_transform.translation));
     }
 }
 
-pub fn despawn_shroom_field(mut commands: Commands, mushroom_query: Query<Entity, With<Mushroom>>) {
+pub fn despawn_shroom_field(
+    mut commands: Commands,
+    mushroom_query: Query<Entity, With<Mushroom>>,
+    mut mushroom_grid: ResMut<MushroomGrid>,
+) {
     for mushroom_entity in mushroom_query.iter() {
         commands.entity(mushroom_entity).despawn();
     }
+    mushroom_grid.clear();
 }
 
 pub fn update_shroom_color(
@@ -102,23 +172,29 @@ pub fn update_shroom_color(
     }
 }
 
-pub fn update_shroom_sprite(mut shroom_q: Query<(&mut Health, &mut TextureAtlas), With<Mushroom>>) {
+pub fn update_shroom_sprite(
+    mut shroom_q: Query<(&mut Health, &mut TextureAtlas), With<Mushroom>>,
+    tuning: Res<Tuning>,
+) {
     for (health, mut atlas) in shroom_q.iter_mut() {
-        match health.0 {
-            3 => atlas.index = 0,
-            2 => atlas.index = 1,
-            1 => atlas.index = 2,
-            _ => return,
+        if health.0 <= 0 {
+            return;
         }
+        // Spread the three damaged sprites over however much health shrooms have
+        let damage = (tuning.mushroom_health - health.0).max(0) as i32;
+        atlas.index = (damage * 3 / tuning.mushroom_health as i32).min(2) as usize;
     }
 }
 
-pub fn heal_shrooms(mut shroom_q: Query<(&mut Health, &mut Sprite), With<Mushroom>>) {
+pub fn heal_shrooms(
+    mut shroom_q: Query<(&mut Health, &mut Sprite), With<Mushroom>>,
+    tuning: Res<Tuning>,
+) {
     for (mut health, mut sprite) in shroom_q.iter_mut() {
-        if health.0 != MUSHROOM_HEALTH {
+        if health.0 != tuning.mushroom_health {
             sprite.color = MUSHROOM_FRESH_COLOR;
 
-            health.0 = MUSHROOM_HEALTH;
+            health.0 = tuning.mushroom_health;
         }
     }
 }
@@ -0,0 +1,88 @@
+use super::*;
+
+// Sorts segments into square cells, so collision checks only look at what is
+// nearby instead of every segment on the field. Mushrooms don't move, so they
+// are looked up in the `MushroomGrid` instead
+#[derive(Resource, Default)]
+pub struct SpatialGrid {
+    segments: GridLayer,
+}
+
+#[derive(Default)]
+struct GridLayer {
+    cells: HashMap<IVec2, Vec<Entity>>,
+    entity_cells: HashMap<Entity, IVec2>,
+}
+
+impl GridLayer {
+    fn insert(&mut self, entity: Entity, cell: IVec2) {
+        match self.entity_cells.insert(entity, cell) {
+            Some(old_cell) if old_cell == cell => return,
+            Some(old_cell) => self.remove_from_cell(entity, old_cell),
+            None => {}
+        }
+        self.cells.entry(cell).or_default().push(entity);
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if let Some(cell) = self.entity_cells.remove(&entity) {
+            self.remove_from_cell(entity, cell);
+        }
+    }
+
+    fn remove_from_cell(&mut self, entity: Entity, cell: IVec2) {
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            entities.retain(|other| *other != entity);
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    // Every entity in the cells overlapping the square around a position
+    fn near(&self, position: Vec3, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let min = SpatialGrid::cell(position - Vec3::splat(radius));
+        let max = SpatialGrid::cell(position + Vec3::splat(radius));
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+impl SpatialGrid {
+    pub fn cell(position: Vec3) -> IVec2 {
+        (position.truncate() / SPATIAL_GRID_CELL_SIZE)
+            .floor()
+            .as_ivec2()
+    }
+
+    // Segments that might be within `radius`, callers still check the distance
+    pub fn segments_near(&self, position: Vec3, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        self.segments.near(position, radius)
+    }
+}
+
+pub fn update_spatial_grid(
+    mut spatial_grid: ResMut<SpatialGrid>,
+    segment_query: Query<(Entity, &Transform), With<Segment>>,
+) {
+    // Segments still in the same cell are left where they are
+    for (entity, transform) in segment_query.iter() {
+        spatial_grid
+            .segments
+            .insert(entity, SpatialGrid::cell(transform.translation));
+    }
+}
+
+// Runs every frame rather than every tick, as removals are only kept for a
+// couple of frames and a frame can pass without a tick
+pub fn remove_from_spatial_grid(
+    mut spatial_grid: ResMut<SpatialGrid>,
+    mut removed_segments: RemovedComponents<Segment>,
+) {
+    for entity in removed_segments.read() {
+        spatial_grid.segments.remove(entity);
+    }
+}
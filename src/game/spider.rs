@@ -38,6 +38,7 @@ impl Spider {
             },
             Spider(SpiderState::Centering),
             Direction(*direction),
+            Interpolated::new(location_transform.translation),
         ));
     }
 
@@ -76,14 +77,15 @@ pub struct SpiderTimer(pub Timer);
 pub fn spawn_spider(
     mut commands: Commands,
     game_assets: Res<GameAssets>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     mut spider_timer: ResMut<SpiderTimer>,
     time: Res<Time>,
     spider_query: Query<(), With<Spider>>,
     game_vars: Res<GameVariables>,
-    level: Res<Level>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    if level.0 < 2 {
+    // Only run on levels with spiders
+    if !game_vars.spider_enabled {
         return;
     }
     // Only run if no current spider
@@ -98,25 +100,23 @@ pub fn spawn_spider(
     }
 
     // Generate a random num to determine if the spider should spawn
-    let run = rand::thread_rng().gen_bool(SPIDER_SPAWN_RATE);
+    let run = game_rng.gen_bool(game_vars.spider_spawn_rate);
     if !run {
         return;
     }
 
-    let window = window_query.get_single().unwrap();
-
     let mut x_start = 0.0;
     let mut direction = Vec3::X;
     let mut y_start = game_vars.spider_average_spawn_height;
 
-    let random_side = rand::thread_rng().gen_bool(0.5);
+    let random_side = game_rng.gen_bool(0.5);
 
     if random_side {
-        x_start = window.width();
+        x_start = playfield.width;
         direction = -Vec3::X;
     }; // Switch to the right side 50% of the time
 
-    y_start = rand::thread_rng().gen_range(y_start - 50.0..50.0 + y_start);
+    y_start = game_rng.gen_range(y_start - 50.0..50.0 + y_start);
 
     let location_transform = Transform::from_xyz(x_start, y_start, 0.0);
     Spider::spawn(&location_transform, &direction, &mut commands, &game_assets)
@@ -124,47 +124,47 @@ pub fn spawn_spider(
 
 pub fn set_spider_direction(
     mut spider_query: Query<(&Transform, &mut Direction, &mut Spider)>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     player_query: Query<&Transform, With<Player>>,
     game_vars: Res<GameVariables>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
 ) {
     // Only run if spider exists
     if let Ok((transform, mut direction, mut spider)) = spider_query.get_single_mut() {
         match spider.0 {
             SpiderState::Centering => {
-                let window = window_query.get_single().unwrap();
-
                 // If on right of center, direction goes left, and vice versa. Check if on center.
                 // I need to check between a threshold because being exactly on center is difficult
                 // to calculate
-                if transform.translation.x >= window.width() / 2.0 - 2.0
-                    && transform.translation.x <= window.width() / 2.0 + 2.0
+                if transform.translation.x >= playfield.width / 2.0 - 2.0
+                    && transform.translation.x <= playfield.width / 2.0 + 2.0
                 {
                     spider.0 = SpiderState::Wandering;
-                } else if transform.translation.x > window.width() / 2.0 {
+                } else if transform.translation.x > playfield.width / 2.0 {
                     direction.0 = -Vec3::X; //Left
                 } else {
                     direction.0 = Vec3::X; // Right
                 }
 
-                let y = rand::thread_rng().gen_range(-0.25..0.25);
+                let y = game_rng.gen_range(-0.25..0.25);
                 direction.0.y = y;
             }
             SpiderState::Wandering => {
                 // Only change direction by random chance
-                let run = rand::thread_rng().gen_bool(SPIDER_DIRECTION_CHANGE_RATE);
+                let run = game_rng.gen_bool(tuning.spider_direction_change_rate);
                 if !run {
                     return;
                 }
 
-                let x = rand::thread_rng().gen_range(-1.0..1.0);
-                let y = rand::thread_rng().gen_range(-1.0..1.0);
+                let x = game_rng.gen_range(-1.0..1.0);
+                let y = game_rng.gen_range(-1.0..1.0);
 
                 let new_direction = Vec3::new(x, y, 0.0).normalize_or_zero();
                 direction.0 = new_direction;
 
                 // Randomly set to attack
-                let attack = rand::thread_rng().gen_bool(game_vars.spider_attack_rate);
+                let attack = game_rng.gen_bool(game_vars.spider_attack_rate);
                 if !attack {
                     return;
                 }
@@ -187,7 +187,7 @@ pub fn set_spider_direction(
                 // Once the spider hits the bottom, it switch back to wandering mode or leave
                 if transform.translation.y <= 0.0 {
                     // Randomly decide to leave
-                    let leave = rand::thread_rng().gen_bool(game_vars.spider_leave_rate);
+                    let leave = game_rng.gen_bool(game_vars.spider_leave_rate);
                     if leave {
                         spider.0 = SpiderState::Leaving;
                     } else {
@@ -197,7 +197,7 @@ pub fn set_spider_direction(
             }
             SpiderState::Leaving => {
                 if !(direction.0.x == -1.0 || direction.0.x == 1.0) {
-                    let right = rand::thread_rng().gen_bool(0.5);
+                    let right = game_rng.gen_bool(0.5);
                     if right {
                         direction.0.x = 1.0;
                     } else {
@@ -214,29 +214,27 @@ pub fn move_spider(
     mut spider_query: Query<(&mut Transform, &mut Direction)>,
     time: Res<Time>,
     game_vars: Res<GameVariables>,
+    slow_motion: Res<SlowMotion>,
 ) {
     // Only run if a spider exists
     if let Ok((mut spider_transform, direction)) = spider_query.get_single_mut() {
         // Move in direction
-        spider_transform.translation.x +=
-            direction.0.x * game_vars.spider_speed * time.delta_seconds();
-        spider_transform.translation.y +=
-            direction.0.y * game_vars.spider_speed * time.delta_seconds() * 1.5;
+        let speed = game_vars.spider_speed * slow_motion.factor();
+        spider_transform.translation.x += direction.0.x * speed * time.delta_seconds();
+        spider_transform.translation.y += direction.0.y * speed * time.delta_seconds() * 1.5;
     }
 }
 
 pub fn confine_spider_movement(
     mut spider_query: Query<(&mut Transform, &mut Direction, &Spider)>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
 ) {
     // Run only if spider exists
     if let Ok((mut transform, mut direction, spider)) = spider_query.get_single_mut() {
-        let window = window_query.get_single().unwrap();
-
-        let upper_bound = window.height() - TOP_UI_HEIGHT;
+        let upper_bound = playfield.height - TOP_UI_HEIGHT;
         let lower_bound = 0.0;
         let left_bound = 0.0;
-        let right_bound = window.width();
+        let right_bound = playfield.width;
 
         match spider.0 {
             SpiderState::Leaving => {}
@@ -268,17 +266,15 @@ pub fn confine_spider_movement(
 pub fn despawn_spider(
     spider_query: Query<(&Transform, Entity, &Spider)>,
     spider_timer: ResMut<SpiderTimer>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
     mut commands: Commands,
 ) {
     if let Ok((transform, entity, spider)) = spider_query.get_single() {
         match spider.0 {
-            SpiderState::Leaving => {
-                let window = window_query.get_single().unwrap();
-
-                if transform.translation.x < 0.0 || transform.translation.y > window.width() {
-                    Spider::despawn(entity, &mut commands, spider_timer);
-                }
+            SpiderState::Leaving
+                if transform.translation.x < 0.0 || transform.translation.y > playfield.width =>
+            {
+                Spider::despawn(entity, &mut commands, spider_timer);
             }
             _ => {}
         }
@@ -321,6 +317,9 @@ pub fn spider_hits_player(
 pub fn spider_eats_shroom(
     spider_query: Query<&Transform, With<Spider>>,
     mut mushroom_query: Query<(&Transform, &mut Health), With<Mushroom>>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+    mushroom_grid: Res<MushroomGrid>,
 ) {
     // Only run if spider exists
     let spider_transform = match spider_query.get_single() {
@@ -331,7 +330,13 @@ pub fn spider_eats_shroom(
     let spider_radius = SPIDER_SIZE / 2.0;
     let shroom_radius = MUSHROOM_SIZE / 2.0;
 
-    for (shroom_transform, mut shroom_health) in mushroom_query.iter_mut() {
+    let nearby_shrooms =
+        mushroom_grid.near(spider_transform.translation, spider_radius + shroom_radius);
+    for shroom_entity in nearby_shrooms {
+        let Ok((shroom_transform, mut shroom_health)) = mushroom_query.get_mut(shroom_entity)
+        else {
+            continue;
+        };
         let distance = shroom_transform
             .translation
             .distance(spider_transform.translation);
@@ -340,7 +345,7 @@ pub fn spider_eats_shroom(
         }
 
         // Randomly eat a shroom
-        let eat_shroom = rand::thread_rng().gen_bool(SPIDER_EAT_RATE);
+        let eat_shroom = game_rng.gen_bool(tuning.spider_eat_rate);
         if !eat_shroom {
             continue;
         }
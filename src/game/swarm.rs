@@ -0,0 +1,219 @@
+// Every few levels the millipede round is followed by a bonus wave of bees
+// flying down in formation. Each bee shot in the wave is worth more than the
+// last, and a tally of the wave is shown before the next millipede arrives
+
+use super::*;
+use bevy::utils::Duration;
+use rand::Rng;
+
+#[derive(Component)]
+pub struct Bee {
+    // Where the bee sits in the formation before it sways
+    formation_x: f32,
+}
+
+impl Bee {
+    pub fn spawn(
+        starting_transform: &Transform,
+        commands: &mut Commands,
+        game_assets: &Res<GameAssets>,
+    ) {
+        commands.spawn((
+            Bee {
+                formation_x: starting_transform.translation.x,
+            },
+            SpriteSheetBundle {
+                texture: game_assets.bee_texture.clone(),
+                transform: *starting_transform,
+                atlas: TextureAtlas {
+                    layout: game_assets.bee_layout.clone(),
+                    index: 0,
+                },
+                ..default()
+            },
+            Animation::new(2, BEE_ANIMATION_TIME),
+            Name::from("Bee"),
+            Interpolated::new(starting_transform.translation),
+        ));
+    }
+
+    pub fn despawn(bee_entity: Entity, commands: &mut Commands) {
+        commands.entity(bee_entity).despawn();
+    }
+
+    // Returns the points the bee was worth
+    pub fn kill(
+        bee_entity: Entity,
+        commands: &mut Commands,
+        score: &mut ResMut<Score>,
+        swarm_wave: &mut SwarmWave,
+    ) -> u32 {
+        Self::despawn(bee_entity, commands);
+        let reward = swarm_wave.next_reward;
+        swarm_wave.next_reward += swarm_wave.reward_increase;
+        swarm_wave.kills += 1;
+        swarm_wave.points += reward;
+        score.0 += reward;
+        reward
+    }
+}
+
+// The wave in progress, or the last one once it's being tallied
+#[derive(Resource, Default)]
+pub struct SwarmWave {
+    pub bees: u32,
+    pub kills: u32,
+    pub points: u32,
+    // What the next bee shot is worth, going up with every kill
+    next_reward: u32,
+    reward_increase: u32,
+    // Drives the formation's sway
+    elapsed: f32,
+    tally_timer: Timer,
+}
+
+impl SwarmWave {
+    // Levels whose millipede is followed by a wave, e.g. every third one
+    pub fn follows_level(level: u32, tuning: &Tuning) -> bool {
+        level.checked_rem(tuning.swarm_wave_interval) == Some(0)
+    }
+}
+
+pub fn start_swarm_wave(
+    mut commands: Commands,
+    mut swarm_wave: ResMut<SwarmWave>,
+    playfield: Res<Playfield>,
+    game_assets: Res<GameAssets>,
+    mut game_rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+) {
+    let bees = game_rng.gen_range(tuning.swarm_min_bees..=tuning.swarm_max_bees);
+    *swarm_wave = SwarmWave {
+        bees,
+        next_reward: tuning.bee_reward,
+        reward_increase: tuning.bee_reward_increase,
+        tally_timer: Timer::from_seconds(SWARM_TALLY_TIME, TimerMode::Once),
+        ..default()
+    };
+
+    // Rows of bees centred on the field, stacked up from just above the HUD
+    let columns = BEE_FORMATION_COLUMNS.min(bees).max(1);
+    let left = (playfield.width - (columns - 1) as f32 * BEE_FORMATION_SPACING) / 2.0;
+    let top = playfield.height - TOP_UI_HEIGHT;
+    for bee in 0..bees {
+        let column = bee % columns;
+        let row = bee / columns;
+        let x = left + column as f32 * BEE_FORMATION_SPACING;
+        let y = top + row as f32 * BEE_FORMATION_SPACING;
+        Bee::spawn(&Transform::from_xyz(x, y, 0.0), &mut commands, &game_assets);
+    }
+}
+
+pub fn move_bees(
+    mut bee_query: Query<(&mut Transform, &Bee)>,
+    mut swarm_wave: ResMut<SwarmWave>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    slow_motion: Res<SlowMotion>,
+) {
+    let delta = time.delta_seconds() * slow_motion.factor();
+    swarm_wave.elapsed += delta;
+
+    // The whole formation sways from side to side as one
+    let sway = (swarm_wave.elapsed * BEE_SWAY_SPEED).sin() * BEE_SWAY_WIDTH;
+    for (mut bee_transform, bee) in bee_query.iter_mut() {
+        bee_transform.translation.y -= tuning.bee_speed * delta;
+        bee_transform.translation.x = bee.formation_x + sway;
+    }
+}
+
+pub fn despawn_bees(bee_query: Query<(&Transform, Entity), With<Bee>>, mut commands: Commands) {
+    for (bee_transform, bee_entity) in bee_query.iter() {
+        // Despawn once it has flown off the bottom
+        if bee_transform.translation.y < 0.0 {
+            Bee::despawn(bee_entity, &mut commands);
+        }
+    }
+}
+
+pub fn bee_hits_player(
+    bee_query: Query<&Transform, With<Bee>>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut next_player_state: ResMut<NextState<PlayerState>>,
+    mut commands: Commands,
+    mut down_timer: ResMut<DownTimer>,
+    mut lives: ResMut<Lives>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    let Ok((player_entity, player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let hit = bee_query.iter().any(|bee_transform| {
+        bee_transform
+            .translation
+            .distance(player_transform.translation)
+            < (BEE_SIZE + PLAYER_SIZE) / 2.0
+    });
+    if hit {
+        Player::kill(
+            player_transform,
+            player_entity,
+            &mut next_player_state,
+            &mut commands,
+            &mut down_timer,
+            &mut lives,
+            &mut explosion_events,
+        )
+    }
+}
+
+// The wave is over once every bee is shot or gone, or the player is hit
+pub fn end_swarm_wave(
+    mut commands: Commands,
+    bee_query: Query<Entity, With<Bee>>,
+    player_state: Res<State<PlayerState>>,
+    mut next_level_state: ResMut<NextState<LevelState>>,
+) {
+    if !bee_query.is_empty() && *player_state.get() == PlayerState::Alive {
+        return;
+    }
+
+    for bee_entity in bee_query.iter() {
+        Bee::despawn(bee_entity, &mut commands);
+    }
+    next_level_state.set(LevelState::Tally);
+}
+
+// Shows the tally for a while, then carries on with the next level
+pub fn tally_swarm_wave(
+    mut swarm_wave: ResMut<SwarmWave>,
+    time: Res<Time>,
+    mut down_timer: ResMut<DownTimer>,
+    mut next_level_state: ResMut<NextState<LevelState>>,
+) {
+    if !swarm_wave.tally_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    // The tally was the downtime, so the next millipede comes straight away
+    down_timer.0.reset();
+    down_timer
+        .0
+        .set_elapsed(Duration::from_secs_f32(DOWNTIMER - 0.01));
+    next_level_state.set(LevelState::Changing);
+}
+
+pub fn animate_bees(
+    mut bee_query: Query<(&mut TextureAtlas, &mut Animation), With<Bee>>,
+    time: Res<Time>,
+) {
+    for (mut atlas, mut animation) in bee_query.iter_mut() {
+        animation.timer.tick(time.delta());
+        if animation.timer.finished() {
+            animation.current_frame = (animation.current_frame + 1) % animation.frames;
+            atlas.index = animation.current_frame;
+
+            animation.timer.reset();
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+
+// A finger being dragged to steer the player. The player follows the finger's
+// movement rather than its position, so the finger can rest anywhere below the
+// player instead of covering it
+#[derive(Resource, Default)]
+pub struct TouchDrag(pub Option<TouchAnchor>);
+
+pub struct TouchAnchor {
+    pub id: u64,
+    pub touch_start: Vec2,
+    pub player_start: Vec3,
+}
+
+// Adds touch steering to the other inputs, and fires for as long as a finger
+// is down
+pub fn read_touch_input(
+    touches: Res<Touches>,
+    player_query: Query<&Transform, With<Player>>,
+    mut touch_drag: ResMut<TouchDrag>,
+    mut player_input: ResMut<PlayerInput>,
+    playfield: Res<Playfield>,
+    tuning: Res<Tuning>,
+    time: Res<Time>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        // Pick the player back up where it respawns
+        touch_drag.0 = None;
+        return;
+    };
+
+    // Only a touch that starts in the player area takes the controls, so tapping
+    // elsewhere on the screen doesn't yank the player around
+    let touch = match &touch_drag.0 {
+        Some(anchor) => touches.get_pressed(anchor.id),
+        None => touches
+            .iter()
+            .find(|touch| playfield.height - touch.start_position().y < TOP_BOUND),
+    };
+    let Some(touch) = touch else {
+        touch_drag.0 = None;
+        return;
+    };
+    let anchor = touch_drag.0.get_or_insert(TouchAnchor {
+        id: touch.id(),
+        touch_start: touch.position(),
+        player_start: player_transform.translation,
+    });
+
+    // The window is the playfield at 1:1, but screen y points down
+    let dragged = touch.position() - anchor.touch_start;
+    let target = anchor.player_start + Vec3::new(dragged.x, -dragged.y, 0.0);
+
+    // Head for the finger at up to full speed
+    let max_step = tuning.player_speed * time.delta_seconds();
+    if max_step > 0.0 {
+        let offset = (target - player_transform.translation).truncate() / max_step;
+        player_input.movement = (player_input.movement + offset).clamp(Vec2::NEG_ONE, Vec2::ONE);
+    }
+    player_input.fire = true;
+}
@@ -0,0 +1,129 @@
+use super::*;
+use bevy::{
+    input::mouse::MouseMotion,
+    window::{CursorGrabMode, PrimaryWindow},
+};
+use serde::{Deserialize, Serialize};
+
+const TRACKBALL_KEY: &str = "trackball";
+
+// Optional arcade style control, where rolling the mouse like the cabinet's
+// trackball pushes the player along and holding the mouse button fires
+#[derive(Resource, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackballSettings {
+    pub enabled: bool,
+    // How far the player moves for each pixel the mouse moves
+    pub sensitivity: f32,
+    // How much of its speed the ball keeps each tick, 0 stops it dead
+    pub inertia: f32,
+}
+
+impl Default for TrackballSettings {
+    fn default() -> Self {
+        TrackballSettings {
+            enabled: false,
+            sensitivity: TRACKBALL_DEFAULT_SENSITIVITY,
+            inertia: TRACKBALL_DEFAULT_INERTIA,
+        }
+    }
+}
+
+impl TrackballSettings {
+    pub fn load() -> Self {
+        let Some(saved) = crate::storage::load(TRACKBALL_KEY) else {
+            return TrackballSettings::default();
+        };
+        ron::from_str(&saved).unwrap_or_else(|error| {
+            warn!("Could not read saved trackball settings: {}", error);
+            TrackballSettings::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let saved = match ron::to_string(self) {
+            Ok(saved) => saved,
+            Err(error) => {
+                warn!("Could not save trackball settings: {}", error);
+                return;
+            }
+        };
+        if let Err(error) = crate::storage::save(TRACKBALL_KEY, &saved) {
+            warn!("Could not save trackball settings: {}", error);
+        }
+    }
+
+    pub fn adjust_sensitivity(&mut self, steps: f32) {
+        self.sensitivity = (self.sensitivity + steps * TRACKBALL_SENSITIVITY_STEP)
+            .clamp(TRACKBALL_SENSITIVITY_STEP, TRACKBALL_MAX_SENSITIVITY);
+    }
+
+    pub fn adjust_inertia(&mut self, steps: f32) {
+        self.inertia =
+            (self.inertia + steps * TRACKBALL_INERTIA_STEP).clamp(0.0, TRACKBALL_MAX_INERTIA);
+    }
+}
+
+// How fast the ball is spinning, in pixels per second
+#[derive(Resource, Default)]
+pub struct Trackball {
+    pub velocity: Vec2,
+}
+
+// Adds the ball's spin to the keyboard and gamepad input. The result goes
+// through `PlayerInput`, so trackball runs record and replay like any other
+pub fn read_trackball_input(
+    mut mouse_motion: EventReader<MouseMotion>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    settings: Res<TrackballSettings>,
+    mut trackball: ResMut<Trackball>,
+    mut player_input: ResMut<PlayerInput>,
+    tuning: Res<Tuning>,
+    time: Res<Time>,
+) {
+    let motion: Vec2 = mouse_motion.read().map(|event| event.delta).sum();
+    if !settings.enabled || time.delta_seconds() == 0.0 {
+        trackball.velocity = Vec2::ZERO;
+        return;
+    }
+
+    // Each push is spread over the ticks it takes the ball to slow down, so the
+    // player travels the same distance whatever the inertia. Screen y points down
+    let push = Vec2::new(motion.x, -motion.y) * settings.sensitivity * (1.0 - settings.inertia);
+    let max_speed = Vec2::splat(tuning.player_speed);
+    let velocity = (trackball.velocity + push / time.delta_seconds()).clamp(-max_speed, max_speed);
+
+    player_input.movement =
+        (player_input.movement + velocity / tuning.player_speed).clamp(Vec2::NEG_ONE, Vec2::ONE);
+    player_input.fire |= mouse_buttons.pressed(MouseButton::Left);
+
+    trackball.velocity = velocity * settings.inertia;
+}
+
+pub fn reset_trackball(mut trackball: ResMut<Trackball>) {
+    trackball.velocity = Vec2::ZERO;
+}
+
+// Keeps the mouse captured while playing with the trackball. The web build uses
+// pointer lock, so the mouse never runs into the edge of the page
+pub fn grab_cursor(
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    settings: Res<TrackballSettings>,
+    app_state: Res<State<AppState>>,
+    game_state: Res<State<GameState>>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    let playing = *app_state.get() == AppState::InGame && *game_state.get() == GameState::Running;
+    let grab_mode = if !settings.enabled || !playing {
+        CursorGrabMode::None
+    } else if cfg!(target_arch = "wasm32") {
+        CursorGrabMode::Locked
+    } else {
+        CursorGrabMode::Confined
+    };
+    if window.cursor.grab_mode != grab_mode {
+        window.cursor.grab_mode = grab_mode;
+        window.cursor.visible = grab_mode == CursorGrabMode::None;
+    }
+}
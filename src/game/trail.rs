@@ -0,0 +1,115 @@
+use super::*;
+use std::collections::VecDeque;
+
+// The positions a segment has passed through, newest first. Each body segment
+// sits a fixed distance back along its parent's trail, so the whole millipede
+// traces the head's exact path. A body segment that becomes a head keeps its
+// own trail, which is already the path the old head took
+#[derive(Component, Default)]
+pub struct Trail(pub VecDeque<Vec3>);
+
+impl Trail {
+    fn record(&mut self, position: Vec3) {
+        if self.0.front() != Some(&position) {
+            self.0.push_front(position);
+        }
+    }
+
+    // The point `distance` back along the trail. The trail is treated as carrying
+    // on to `end`, so a segment that hasn't reached it yet is pulled onto it
+    fn point_back(&self, distance: f32, end: Vec3) -> Option<Vec3> {
+        let mut travelled = 0.0;
+        let mut points = self.0.iter().copied().chain(std::iter::once(end));
+        let mut previous = points.next()?;
+        for point in points {
+            let length = previous.distance(point);
+            if travelled + length >= distance && length > 0.0 {
+                return Some(previous.lerp(point, (distance - travelled) / length));
+            }
+            travelled += length;
+            previous = point;
+        }
+        None
+    }
+
+    // Drops the points further back than `distance`, nothing follows them
+    fn trim(&mut self, distance: f32) {
+        let mut travelled = 0.0;
+        for index in 1..self.0.len() {
+            travelled += self.0[index - 1].distance(self.0[index]);
+            if travelled >= distance {
+                self.0.truncate(index + 1);
+                return;
+            }
+        }
+    }
+}
+
+pub fn follow_trails(
+    mut segment_query: Query<(
+        &Segment,
+        &ChainLink,
+        &mut Transform,
+        &mut Trail,
+        &mut Animation,
+    )>,
+    time: Res<Time>,
+) {
+    let mut first_children = Vec::new();
+    for (segment, link, transform, mut trail, _) in segment_query.iter_mut() {
+        if let Segment::Head { .. } = segment {
+            trail.record(transform.translation);
+            first_children.push(link.child);
+
+            // A head on its own is also a tail
+            if link.child.is_none() {
+                trail.trim(SEGMENT_SPACING);
+            }
+        }
+    }
+
+    // Walk each millipede from the head, so parents have moved before their children
+    for first_child in first_children {
+        let mut next_entity = first_child;
+        while let Some(entity) = next_entity {
+            let Ok((_, link, transform, _, _)) = segment_query.get(entity) else {
+                break;
+            };
+            next_entity = link.child;
+            let Some(parent_entity) = link.parent else {
+                break;
+            };
+            let previous = transform.translation;
+
+            let Ok((_, _, _, mut parent_trail, _)) = segment_query.get_mut(parent_entity) else {
+                break;
+            };
+            let target = parent_trail.point_back(SEGMENT_SPACING, previous);
+            parent_trail.trim(SEGMENT_SPACING);
+
+            let Ok((_, _, mut transform, mut trail, mut animation)) = segment_query.get_mut(entity)
+            else {
+                break;
+            };
+            if let Some(target) = target {
+                transform.translation = target;
+            }
+            trail.record(transform.translation);
+
+            // Face along the trail and animate if moved
+            let moved = transform.translation - previous;
+            if moved.length() > 0.0 {
+                let angle = moved.y.atan2(moved.x);
+                transform.rotation = Quat::from_rotation_z(angle + std::f32::consts::FRAC_PI_2);
+            }
+            if moved.length() > 1.0 {
+                animation.timer.tick(time.delta());
+            }
+
+            // Nothing follows the tail, so keep its trail short
+            if next_entity.is_none() {
+                trail.trim(SEGMENT_SPACING);
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use super::*;
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+// The tuning the game is built with, also used when running headless
+const DEFAULT_TUNING: &str = include_str!("../../assets/tuning/default.tuning.ron");
+
+// Balance numbers, loaded from assets/tuning/<name>.tuning.ron so they can be
+// changed without recompiling
+#[derive(Asset, Resource, TypePath, Deserialize, Clone)]
+pub struct Tuning {
+    // Player
+    pub player_speed: f32,
+    pub starting_lives: u8,
+
+    // Projectile
+    pub projectile_speed: f32,
+    pub projectile_acceleration: f32,
+
+    // Mushrooms
+    pub mushroom_max_amount: u8,
+    pub mushroom_min_amount: u8,
+    pub mushroom_health: i8,
+
+    // Millipede
+    #[serde(default)]
+    pub millipede_movement: MillipedeMovement,
+    pub segment_drop_rate: f64,
+
+    // Beetle
+    pub beetle_speed: f32,
+    pub beetle_spawn_rate: u8,
+
+    // Spider
+    pub spider_direction_change_rate: f64,
+    pub spider_eat_rate: f64,
+
+    // Scorpion
+    pub scorpion_spawn_rate: f64,
+    pub scorpion_speed: f32,
+    pub mushroom_conversion_rate: f64,
+
+    // Earwig
+    pub earwig_spawn_rate: f64,
+    pub earwig_speed: f32,
+
+    // Inchworm
+    pub inchworm_spawn_rate: f64,
+    pub inchworm_speed: f32,
+    // How long shooting it slows enemies down, and how slow they go
+    pub slow_motion_duration: f32,
+    pub slow_motion_factor: f32,
+
+    // Dragonfly
+    pub dragonfly_spawn_rate: f64,
+    pub dragonfly_speed: f32,
+    // Chance each tick of dropping a mushroom in the player area
+    pub dragonfly_drop_rate: f64,
+
+    // Swarm waves
+    // A wave follows every level divisible by this, 0 for none
+    pub swarm_wave_interval: u32,
+    pub swarm_min_bees: u32,
+    pub swarm_max_bees: u32,
+    pub bee_speed: f32,
+
+    // Scoring
+    pub mushroom_reward: u32,
+    pub segment_reward: u32,
+    pub head_reward: u32,
+    pub beetle_reward: u32,
+    pub scorpion_reward: u32,
+    pub earwig_reward: u32,
+    pub inchworm_reward: u32,
+    pub dragonfly_reward: u32,
+    // Each bee shot in a wave is worth this much more than the last
+    pub bee_reward: u32,
+    pub bee_reward_increase: u32,
+
+    // Levels, starting with level 1
+    pub levels: Vec<GameVariables>,
+    pub level_growth: LevelGrowth,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Tuning::from_ron(DEFAULT_TUNING.as_bytes()).expect("Built in tuning file is invalid")
+    }
+}
+
+impl Tuning {
+    pub fn path(name: &str) -> String {
+        format!("tuning/{}.tuning.ron", name)
+    }
+
+    // Reads a tuning file straight from disk, for when there is no asset server
+    pub fn load_file(name: &str) -> Result<Self, String> {
+        let path = format!("assets/{}", Tuning::path(name));
+        let file = std::fs::read(path).map_err(|error| error.to_string())?;
+        Tuning::from_ron(&file).map_err(|error| error.to_string())
+    }
+
+    fn from_ron(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let tuning: Tuning = ron::de::from_bytes(bytes)?;
+        if tuning.levels.is_empty() {
+            return Err("tuning needs at least one level".into());
+        }
+        if tuning.swarm_min_bees == 0 || tuning.swarm_min_bees > tuning.swarm_max_bees {
+            return Err("swarm_min_bees must be at least 1 and at most swarm_max_bees".into());
+        }
+        if tuning.mushroom_health <= 0 {
+            return Err("mushroom_health must be at least 1".into());
+        }
+
+        // Timers panic on lengths that aren't a finite number of seconds
+        let mut durations = vec![("slow_motion_duration", tuning.slow_motion_duration)];
+        for level in &tuning.levels {
+            durations.push(("spider_timer_length", level.spider_timer_length));
+            durations.push((
+                "segment_spawn_timer_length",
+                level.segment_spawn_timer_length,
+            ));
+        }
+        for (name, seconds) in durations {
+            if !seconds.is_finite() || seconds < 0.0 {
+                return Err(format!("{} must be 0 seconds or more", name).into());
+            }
+        }
+        Ok(tuning)
+    }
+
+    // The difficulty of a level. Levels past the end of the table keep growing
+    // from the last one listed, up to `MAX_LEVEL_GROWTH` levels past it
+    pub fn level(&self, level: u32) -> GameVariables {
+        let index = level.max(1) as usize - 1;
+        if let Some(game_vars) = self.levels.get(index) {
+            return game_vars.clone();
+        }
+
+        let mut game_vars = self.levels[self.levels.len() - 1].clone();
+        let growth_steps = (index + 1 - self.levels.len()).min(MAX_LEVEL_GROWTH);
+        for _ in 0..growth_steps {
+            game_vars.grow(&self.level_growth);
+        }
+        game_vars
+    }
+}
+
+#[derive(Default)]
+pub struct TuningLoader;
+
+impl AssetLoader for TuningLoader {
+    type Asset = Tuning;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Tuning, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Tuning::from_ron(&bytes)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
+
+// The name of the tuning being played, kept so replays can record it
+#[derive(Resource)]
+pub struct TuningName(pub String);
+
+// Keeps the tuning file loaded so it is reloaded when it changes
+#[derive(Resource)]
+pub struct TuningHandle {
+    handle: Handle<Tuning>,
+    // Loaded or changed since it was last applied
+    pending: bool,
+    applied: bool,
+}
+
+impl TuningHandle {
+    pub fn new(handle: Handle<Tuning>) -> Self {
+        TuningHandle {
+            handle,
+            pending: false,
+            applied: false,
+        }
+    }
+}
+
+// A run and its replay play with one tuning from start to finish, so a loaded
+// or changed file only takes over between runs
+pub fn apply_tuning(
+    mut tuning_events: EventReader<AssetEvent<Tuning>>,
+    tuning_assets: Res<Assets<Tuning>>,
+    mut tuning_handle: ResMut<TuningHandle>,
+    mut tuning: ResMut<Tuning>,
+    app_state: Res<State<AppState>>,
+) {
+    for event in tuning_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if *id == tuning_handle.handle.id() {
+                tuning_handle.pending = true;
+            }
+        }
+    }
+
+    if !tuning_handle.pending || *app_state.get() == AppState::InGame {
+        return;
+    }
+    let Some(loaded_tuning) = tuning_assets.get(&tuning_handle.handle) else {
+        return;
+    };
+    *tuning = loaded_tuning.clone();
+    tuning_handle.pending = false;
+    tuning_handle.applied = true;
+
+    info!("Applied tuning from {:?}", tuning_handle.handle.path());
+}
+
+// Holds off starting a run until the tuning file has been applied, so it never
+// starts on the built in tuning and switches partway through
+pub fn wait_for_tuning(
+    tuning_handle: Res<TuningHandle>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut waiting: Local<bool>,
+) {
+    if tuning_handle.applied {
+        if *waiting {
+            next_app_state.set(AppState::InGame);
+            *waiting = false;
+        }
+        return;
+    }
+    if next_app_state.0 == Some(AppState::InGame) {
+        next_app_state.0 = None;
+        *waiting = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The parts of a level that `LevelGrowth` changes
+    fn grown_parts(game_vars: &GameVariables) -> (usize, f32, f32, f64, f64) {
+        (
+            game_vars.millipede_length,
+            game_vars.millipede_speed,
+            game_vars.spider_speed,
+            game_vars.spider_attack_rate,
+            game_vars.spider_leave_rate,
+        )
+    }
+
+    fn grown(tuning: &Tuning, steps: usize) -> (usize, f32, f32, f64, f64) {
+        let mut game_vars = tuning.levels[tuning.levels.len() - 1].clone();
+        for _ in 0..steps {
+            game_vars.grow(&tuning.level_growth);
+        }
+        grown_parts(&game_vars)
+    }
+
+    #[test]
+    fn levels_come_from_the_table_then_grow() {
+        let tuning = Tuning::default();
+        let listed = tuning.levels.len() as u32;
+
+        let first = grown_parts(&tuning.levels[0]);
+        assert_eq!(grown_parts(&tuning.level(0)), first);
+        assert_eq!(grown_parts(&tuning.level(1)), first);
+        assert_eq!(grown_parts(&tuning.level(listed)), grown(&tuning, 0));
+        assert_eq!(grown_parts(&tuning.level(listed + 1)), grown(&tuning, 1));
+        assert_eq!(grown_parts(&tuning.level(listed + 3)), grown(&tuning, 3));
+        assert_eq!(
+            grown_parts(&tuning.level(u32::MAX)),
+            grown(&tuning, MAX_LEVEL_GROWTH)
+        );
+    }
+
+    fn error(ron: &str) -> String {
+        Tuning::from_ron(ron.as_bytes()).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn rejects_invalid_tuning() {
+        let mushroom_health = DEFAULT_TUNING.replace("mushroom_health: 3", "mushroom_health: 0");
+        assert_eq!(
+            error(&mushroom_health),
+            "mushroom_health must be at least 1"
+        );
+
+        let levels_start = DEFAULT_TUNING.find("levels: [").unwrap();
+        let levels_end = DEFAULT_TUNING.find("level_growth:").unwrap();
+        let no_levels = format!(
+            "{}levels: [],\n    {}",
+            &DEFAULT_TUNING[..levels_start],
+            &DEFAULT_TUNING[levels_end..]
+        );
+        assert_eq!(error(&no_levels), "tuning needs at least one level");
+    }
+}
@@ -0,0 +1,44 @@
+use bevy::{app::AppExit, input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    game::{self, level::Level, rng::GameRng, GameOptions, Score},
+    AppState, GameState, OptionsState,
+};
+
+// Runs the simulation without a window, renderer or audio. Every update
+// advances time by exactly one tick so runs go as fast as the CPU allows.
+pub fn run() {
+    let mut app = build_app(GameOptions::from_args());
+    app.add_systems(OnEnter(AppState::GameOver), report_and_exit)
+        .run();
+}
+
+// A game that starts playing on its first update
+pub fn build_app(options: GameOptions) -> App {
+    let mut app = App::new();
+    app.insert_state(AppState::InGame)
+        .init_state::<GameState>()
+        .init_state::<OptionsState>()
+        .add_plugins((MinimalPlugins, InputPlugin))
+        .add_plugins(game::GamePlugin { options });
+
+    // The plugin picks the tick length, which a replay may override
+    let timestep = app.world.resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    app
+}
+
+fn report_and_exit(
+    score: Res<Score>,
+    level: Res<Level>,
+    game_rng: Res<GameRng>,
+    mut app_exit_events: EventWriter<AppExit>,
+) {
+    println!(
+        "Game over on level {} with a score of {} (seed {})",
+        level.0,
+        score.0,
+        game_rng.seed()
+    );
+    app_exit_events.send(AppExit);
+}
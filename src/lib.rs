@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+pub mod args;
+pub mod audio;
+pub mod camera;
+pub mod constants;
+pub mod debug;
+pub mod env;
+pub mod game;
+pub mod headless;
+pub mod storage;
+pub mod ui;
+
+pub use env::MillipedeEnv;
+
+#[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    InGame,
+    GameOver,
+    Controls,
+    // Passed through for a frame when a game is restarted, so the game is left
+    // and entered again like any other
+    Restarting,
+}
+
+// The options screen opens over the main menu or the pause menu
+#[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub enum OptionsState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Running,
+    Paused,
+}
@@ -1,83 +1,164 @@
 use bevy::prelude::*;
+#[cfg(not(feature = "hot_reload"))]
 use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
 use bevy_kira_audio::prelude::*;
-
-mod audio;
-mod camera;
-mod constants;
-mod debug;
-mod game;
-mod ui;
+use millipede::{
+    args, audio, camera, constants, debug, game, headless, ui, AppState, GameState, OptionsState,
+};
 
 fn main() {
+    // Run the simulation without a window, e.g. for CI
+    if args::flag("--headless") {
+        headless::run();
+        return;
+    }
+
     let window_plugin = WindowPlugin {
         primary_window: Some(Window {
             title: "Millipede".into(),
             canvas: Some("#game-canvas".into()),
-            resolution: (480., 640.).into(),
+            resolution: (constants::PLAYFIELD_WIDTH, constants::PLAYFIELD_HEIGHT).into(),
             resizable: false,
             ..default()
         }),
         ..default()
     };
-    App::new()
-        .init_state::<AppState>()
+    let mut app = App::new();
+    app.init_state::<AppState>()
         .init_state::<GameState>()
-        .add_plugins(EmbeddedAssetPlugin {
-            mode: PluginMode::ReplaceDefault,
-        })
-        .add_plugins(
-            DefaultPlugins
-                .set(ImagePlugin::default_nearest())
-                .set(window_plugin),
-        )
-        .add_plugins(debug::DebugPlugin)
-        .add_plugins(game::GamePlugin)
-        .add_plugins(AudioPlugin)
-        .add_systems(
-            Update,
+        .init_state::<OptionsState>();
+
+    // Hot reloading watches the assets folder, so only embed assets without it
+    #[cfg(not(feature = "hot_reload"))]
+    app.add_plugins(EmbeddedAssetPlugin {
+        mode: PluginMode::ReplaceDefault,
+    });
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(ImagePlugin::default_nearest())
+            .set(window_plugin),
+    )
+    .add_plugins(debug::DebugPlugin)
+    .add_plugins(game::GamePlugin {
+        options: game::GameOptions::from_args(),
+    })
+    .add_plugins(AudioPlugin)
+    .add_systems(
+        Update,
+        (
             (
                 ui::handle_button_actions,
                 ui::handle_button_navigation,
                 ui::update_button_colors,
                 ui::change_score_text_color,
                 ui::change_title_text_color,
+                ui::edit_seed,
+                ui::update_seed_text,
             )
-                .run_if(in_menu),
-        )
-        .add_systems(OnEnter(AppState::GameOver), (ui::spawn_game_over_ui,))
-        .add_systems(OnExit(AppState::GameOver), (ui::despawn_game_over_ui,))
-        .add_systems(
-            OnEnter(AppState::MainMenu),
-            ui::set_default_button_selection,
+                .run_if(in_menu)
+                .run_if(in_state(OptionsState::Closed))
+                .run_if(not(resource_exists::<ui::InitialsEntry>)),
+            // Runs after the menu so the key that finishes the initials
+            // doesn't also press a button
+            ui::enter_initials.run_if(resource_exists::<ui::InitialsEntry>),
         )
-        .add_systems(
-            OnEnter(AppState::GameOver),
+            .chain(),
+    )
+    .add_systems(
+        OnEnter(AppState::GameOver),
+        (ui::spawn_game_over_ui,).run_if(not(bot_playing)),
+    )
+    .add_systems(OnExit(AppState::GameOver), (ui::despawn_game_over_ui,))
+    .add_systems(
+        OnEnter(AppState::MainMenu),
+        ui::set_default_button_selection,
+    )
+    .add_systems(
+        OnEnter(AppState::GameOver),
+        ui::set_default_button_selection.run_if(not(bot_playing)),
+    )
+    .add_systems(Startup, (camera::spawn_game_camera).chain())
+    .add_systems(OnEnter(AppState::MainMenu), ui::spawn_main_menu)
+    .add_systems(OnExit(AppState::MainMenu), ui::despawn_main_menu)
+    .add_systems(OnEnter(AppState::InGame), ui::build_game_ui)
+    .add_systems(
+        OnEnter(AppState::InGame),
+        ui::build_demo_ui
+            .after(ui::build_game_ui)
+            .run_if(resource_exists::<game::demo::DemoMode>),
+    )
+    .add_systems(OnExit(AppState::InGame), ui::despawn_demo_ui)
+    .add_systems(OnEnter(game::LevelState::Tally), ui::spawn_swarm_tally_ui)
+    .add_systems(OnExit(game::LevelState::Tally), ui::despawn_swarm_tally_ui)
+    .add_systems(OnExit(AppState::InGame), ui::despawn_swarm_tally_ui)
+    .add_systems(
+        Update,
+        ui::change_title_text_color
+            .run_if(in_state(AppState::InGame))
+            .run_if(resource_exists::<game::demo::DemoMode>),
+    )
+    .add_systems(
+        OnEnter(GameState::Paused),
+        (
+            ui::spawn_pause_menu,
             ui::set_default_button_selection,
+            audio::pause_tracks,
+        ),
+    )
+    .add_systems(
+        OnExit(GameState::Paused),
+        (ui::despawn_pause_menu, audio::resume_tracks),
+    )
+    .add_systems(
+        Update,
+        (
+            (
+                ui::handle_pause_menu_actions,
+                ui::handle_button_navigation,
+                ui::update_button_colors,
+            )
+                .chain()
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::Paused))
+                .run_if(in_state(OptionsState::Closed)),
+            // Nobody needs to come back to a game the bot is playing
+            ui::pause_on_focus_loss.run_if(not(bot_playing)),
+        ),
+    )
+    .add_systems(OnEnter(OptionsState::Open), ui::spawn_options_menu)
+    .add_systems(OnExit(OptionsState::Open), ui::despawn_options_menu)
+    .add_systems(
+        Update,
+        (ui::navigate_options, ui::update_options_text)
+            .chain()
+            .run_if(in_state(OptionsState::Open)),
+    )
+    .add_systems(OnEnter(AppState::Controls), ui::spawn_controls_menu)
+    .add_systems(OnExit(AppState::Controls), ui::despawn_controls_menu)
+    .add_systems(
+        Update,
+        (ui::navigate_controls, ui::update_controls_text)
+            .chain()
+            .run_if(in_state(AppState::Controls)),
+    )
+    .insert_resource(ui::SelectedButton(ui::ButtonType::Play))
+    .insert_resource(audio::AudioSettings::load())
+    .add_systems(Startup, (audio::prepare_audio).chain())
+    .add_systems(
+        Update,
+        (
+            audio::set_volume,
+            audio::sync_audio,
+            audio::set_playback_rate,
         )
-        .add_systems(Startup, (camera::spawn_game_camera).chain())
-        .add_systems(OnEnter(AppState::MainMenu), ui::spawn_main_menu)
-        .add_systems(OnExit(AppState::MainMenu), ui::despawn_main_menu)
-        .add_systems(OnEnter(AppState::InGame), ui::build_game_ui)
-        .insert_resource(ui::SelectedButton(ui::ButtonType::Play))
-        .add_systems(Startup, (audio::prepare_audio).chain())
-        .add_systems(Update, (audio::set_volume, audio::sync_audio).after(audio::prepare_audio))
-        .run();
-}
-
-#[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
-pub enum AppState {
-    #[default]
-    MainMenu,
-    InGame,
-    GameOver,
+            .after(audio::prepare_audio),
+    )
+    .run();
 }
 
-#[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
-pub enum GameState {
-    #[default]
-    Running,
-    Paused,
+fn bot_playing(player_controller: Res<game::input::PlayerController>) -> bool {
+    *player_controller == game::input::PlayerController::Bot
 }
 
 fn in_menu(state: Res<State<AppState>>) -> bool {
@@ -0,0 +1,30 @@
+// Keeps small pieces of save data between sessions. Native builds write a file
+// next to the game, the web build uses the browser's localStorage
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(format!("{}.txt", key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, value: &str) -> Result<(), String> {
+    std::fs::write(format!("{}.txt", key), value).map_err(|error| error.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, value: &str) -> Result<(), String> {
+    let storage = local_storage().ok_or("localStorage is not available")?;
+    storage
+        .set_item(key, value)
+        .map_err(|_| String::from("localStorage rejected the write"))
+}
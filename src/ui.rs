@@ -1,15 +1,23 @@
 use crate::{
+    audio::AudioSettings,
     constants::*,
     game::{
+        actions::{Action, ActionInput, Binding, Bindings},
         assets::*,
+        high_score::HighScores,
+        inchworm::SlowMotion,
         level::{GameOverTimer, Level},
         player::Lives,
+        playfield::Playfield,
+        rng::{GameRng, GameSeed},
+        swarm::SwarmWave,
+        trackball::TrackballSettings,
         Score,
     },
 };
 
 use super::*;
-use bevy::{app::AppExit, window::PrimaryWindow};
+use bevy::{app::AppExit, ecs::system::SystemParam, ui::FocusPolicy, window::WindowFocused};
 
 #[derive(Component)]
 pub struct MainMenu;
@@ -26,17 +34,29 @@ pub struct SelectedButton(pub ButtonType);
 #[derive(Clone, Copy, Component, PartialEq)]
 pub enum ButtonType {
     Play,
+    Seed,
+    Controls,
     Quit,
     Restart,
     MainMenu,
+    Resume,
+    Options,
 }
 
 #[derive(Component)]
 pub struct TitleText;
 
+#[derive(Component)]
+pub struct SeedText;
+
 // Main Menu
-pub fn spawn_main_menu(mut commands: Commands, game_assets: Res<GameAssets>) {
-    build_main_menu(&mut commands, &game_assets);
+pub fn spawn_main_menu(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    game_seed: Res<GameSeed>,
+    high_scores: Res<HighScores>,
+) {
+    build_main_menu(&mut commands, &game_assets, &game_seed, &high_scores);
 }
 
 pub fn despawn_main_menu(mut commands: Commands, main_menu_query: Query<Entity, With<MainMenu>>) {
@@ -45,7 +65,12 @@ pub fn despawn_main_menu(mut commands: Commands, main_menu_query: Query<Entity,
     }
 }
 
-pub fn build_main_menu(commands: &mut Commands, game_assets: &Res<GameAssets>) {
+pub fn build_main_menu(
+    commands: &mut Commands,
+    game_assets: &Res<GameAssets>,
+    game_seed: &Res<GameSeed>,
+    high_scores: &Res<HighScores>,
+) {
     // Define the main menu parent node with MainMenu marker struct
     let main_menu_node = (
         NodeBundle {
@@ -69,7 +94,7 @@ pub fn build_main_menu(commands: &mut Commands, game_assets: &Res<GameAssets>) {
     // Define the title node
     let title_node = NodeBundle {
         style: Style {
-            height: Val::Percent(80.0),
+            height: Val::Percent(50.0),
             width: Val::Percent(100.0),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
@@ -102,6 +127,44 @@ pub fn build_main_menu(commands: &mut Commands, game_assets: &Res<GameAssets>) {
         })
         .id();
 
+    // Define the high score table node
+    let high_scores_node = NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    };
+
+    let high_scores_entity = commands
+        .spawn(high_scores_node)
+        .with_children(|parent| {
+            if high_scores.0.is_empty() {
+                return;
+            }
+            parent.spawn(TextBundle::from_section(
+                "High Scores",
+                TextStyle {
+                    color: Color::YELLOW,
+                    font_size: 15.0,
+                    font: game_assets.font.clone(),
+                },
+            ));
+            for (rank, entry) in high_scores.0.iter().enumerate() {
+                parent.spawn(TextBundle::from_section(
+                    format!("{:>2}. {} {:07}", rank + 1, entry.initials, entry.score),
+                    TextStyle {
+                        color: Color::WHITE,
+                        font_size: 12.0,
+                        font: game_assets.font.clone(),
+                    },
+                ));
+            }
+        })
+        .id();
+
     // Define the button node
     let buttons_node = NodeBundle {
         style: Style {
@@ -136,6 +199,68 @@ pub fn build_main_menu(commands: &mut Commands, game_assets: &Res<GameAssets>) {
                     ..default()
                 },
                 ButtonType::Play,
+                Interaction::default(),
+            ));
+
+            // Seed Button
+            parent.spawn((
+                TextBundle {
+                    text: Text {
+                        sections: vec![TextSection::new(
+                            seed_label(game_seed),
+                            TextStyle {
+                                color: Color::GREEN,
+                                font_size: 15.0,
+                                font: game_assets.font.clone(),
+                            },
+                        )],
+                        ..default()
+                    },
+                    ..default()
+                },
+                ButtonType::Seed,
+                SeedText,
+                Interaction::default(),
+            ));
+
+            // Controls Button
+            parent.spawn((
+                TextBundle {
+                    text: Text {
+                        sections: vec![TextSection::new(
+                            "Controls",
+                            TextStyle {
+                                color: Color::GREEN,
+                                font_size: 20.0,
+                                font: game_assets.font.clone(),
+                            },
+                        )],
+                        ..default()
+                    },
+                    ..default()
+                },
+                ButtonType::Controls,
+                Interaction::default(),
+            ));
+
+            // Options Button
+            parent.spawn((
+                TextBundle {
+                    text: Text {
+                        sections: vec![TextSection::new(
+                            "Options",
+                            TextStyle {
+                                color: Color::GREEN,
+                                font_size: 20.0,
+                                font: game_assets.font.clone(),
+                            },
+                        )],
+                        ..default()
+                    },
+                    ..default()
+                },
+                ButtonType::Options,
+                Interaction::default(),
             ));
 
             // Quit Button
@@ -156,6 +281,7 @@ pub fn build_main_menu(commands: &mut Commands, game_assets: &Res<GameAssets>) {
                     ..default()
                 },
                 ButtonType::Quit,
+                Interaction::default(),
             ));
         })
         .id();
@@ -192,32 +318,118 @@ pub fn build_main_menu(commands: &mut Commands, game_assets: &Res<GameAssets>) {
         .id();
 
     // Set up parent-child relationships
-    commands
-        .entity(main_menu_entity)
-        .push_children(&[title_entity, buttons_entity, name_entity]);
+    commands.entity(main_menu_entity).push_children(&[
+        title_entity,
+        high_scores_entity,
+        buttons_entity,
+        name_entity,
+    ]);
+}
+
+fn seed_label(game_seed: &GameSeed) -> String {
+    match game_seed.0 {
+        Some(seed) => format!("Seed: {}", seed),
+        None => "Seed: Random".to_string(),
+    }
+}
+
+pub fn update_seed_text(
+    game_seed: Res<GameSeed>,
+    mut seed_text_query: Query<&mut Text, With<SeedText>>,
+) {
+    if game_seed.is_changed() {
+        for mut text in seed_text_query.iter_mut() {
+            text.sections[0].value = seed_label(&game_seed);
+        }
+    }
+}
+
+// Lets the player type in a seed while the seed button is selected
+pub fn edit_seed(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selected_button: Res<SelectedButton>,
+    mut game_seed: ResMut<GameSeed>,
+) {
+    if selected_button.0 != ButtonType::Seed {
+        return;
+    }
+
+    const DIGIT_KEYS: [KeyCode; 10] = [
+        KeyCode::Digit0,
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+
+    for (digit, key) in DIGIT_KEYS.iter().enumerate() {
+        if keyboard_input.just_pressed(*key) {
+            let seed = game_seed.0.unwrap_or(0);
+            // Ignore digits that would overflow the seed
+            if let Some(seed) = seed
+                .checked_mul(10)
+                .and_then(|seed| seed.checked_add(digit as u64))
+            {
+                game_seed.0 = Some(seed);
+            }
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        game_seed.0 = match game_seed.0 {
+            Some(seed) if seed >= 10 => Some(seed / 10),
+            _ => None,
+        };
+    }
 }
 
 #[derive(Component)]
 pub struct ScoreUi;
 
+#[derive(Component)]
+pub struct HighScoreUi;
+
 #[derive(Component)]
 pub struct LivesUi;
 
 #[derive(Component)]
 pub struct LevelUi;
 
-pub fn build_game_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+#[derive(Component)]
+pub struct SlowMotionUi;
+
+#[derive(Component)]
+pub struct GameUi;
+
+pub fn build_game_ui(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    game_ui_query: Query<Entity, With<GameUi>>,
+) {
+    // Replace the previous game's UI
+    for entity in game_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
     let player_icon = &game_assets.player_texture;
     // Load Ui images
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        })
+            GameUi,
+        ))
         .with_children(|parent| {
             // Top UI bar
             parent
@@ -283,6 +495,25 @@ pub fn build_game_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
                             ..default()
                         })
                         .with_children(|parent| {
+                            // High score next to the score
+                            parent.spawn((
+                                TextBundle {
+                                    text: Text::from_section(
+                                        "HI 0",
+                                        TextStyle {
+                                            font: game_assets.font.clone(),
+                                            font_size: TEXT_SIZE,
+                                            color: Color::YELLOW,
+                                        },
+                                    ),
+                                    style: Style {
+                                        margin: UiRect::right(Val::Px(8.0)),
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                HighScoreUi,
+                            ));
                             parent.spawn((
                                 TextBundle {
                                     text: Text::from_section(
@@ -312,6 +543,25 @@ pub fn build_game_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
                             ..default()
                         })
                         .with_children(|parent| {
+                            // Counts down while the inchworm's slow motion lasts
+                            parent.spawn((
+                                TextBundle {
+                                    text: Text::from_section(
+                                        "",
+                                        TextStyle {
+                                            font: game_assets.font.clone(),
+                                            font_size: TEXT_SIZE,
+                                            color: SLOW_MOTION_TEXT_COLOR,
+                                        },
+                                    ),
+                                    style: Style {
+                                        margin: UiRect::right(Val::Px(8.0)),
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                SlowMotionUi,
+                            ));
                             parent.spawn((
                                 TextBundle {
                                     text: Text::from_section(
@@ -340,6 +590,20 @@ pub fn update_score_ui(mut score_query: Query<&mut Text, With<ScoreUi>>, score:
     }
 }
 
+pub fn update_high_score_ui(
+    mut high_score_query: Query<&mut Text, With<HighScoreUi>>,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+) {
+    if score.is_changed() || high_scores.is_changed() {
+        // The current run becomes the high score as soon as it passes the table
+        let high_score = high_scores.best().max(score.0);
+        for mut text in high_score_query.iter_mut() {
+            text.sections[0].value = format!("HI {:07}", high_score);
+        }
+    }
+}
+
 pub fn update_lives_ui(mut lives_query: Query<&mut Text, With<LivesUi>>, lives: Res<Lives>) {
     if lives.is_changed() {
         for mut text in lives_query.iter_mut() {
@@ -356,25 +620,88 @@ pub fn update_level_ui(mut level_query: Query<&mut Text, With<LevelUi>>, level:
     }
 }
 
+// Shows the slow motion time left and tints the playfield while it lasts
+pub fn update_slow_motion_ui(
+    mut slow_motion_query: Query<&mut Text, With<SlowMotionUi>>,
+    mut game_ui_query: Query<&mut BackgroundColor, With<GameUi>>,
+    slow_motion: Res<SlowMotion>,
+) {
+    if !slow_motion.is_changed() {
+        return;
+    }
+    let (value, tint) = if slow_motion.is_active() {
+        (
+            format!("SLOW {:.1}", slow_motion.remaining_secs()),
+            SLOW_MOTION_TINT,
+        )
+    } else {
+        (String::new(), Color::NONE)
+    };
+    for mut text in slow_motion_query.iter_mut() {
+        text.sections[0].value.clone_from(&value);
+    }
+    for mut background_color in game_ui_query.iter_mut() {
+        background_color.0 = tint;
+    }
+}
+
 pub fn handle_button_navigation(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: ActionInput,
     mut selected_button: ResMut<SelectedButton>,
     app_state: Res<State<AppState>>,
 ) {
+    let up = actions.just_pressed(Action::MoveUp);
+    let down = actions.just_pressed(Action::MoveDown);
+
     // Main Menu
     if *app_state.get() == AppState::MainMenu {
-        if keyboard_input.just_pressed(UP) || keyboard_input.just_pressed(DOWN) {
+        if down {
             selected_button.0 = match selected_button.0 {
-                ButtonType::Play => ButtonType::Quit,
+                ButtonType::Play => ButtonType::Seed,
+                ButtonType::Seed => ButtonType::Controls,
+                ButtonType::Controls => ButtonType::Options,
+                ButtonType::Options => ButtonType::Quit,
                 ButtonType::Quit => ButtonType::Play,
                 _ => unreachable!("Tried to set button not in main menu!"),
             }
         }
+        if up {
+            selected_button.0 = match selected_button.0 {
+                ButtonType::Play => ButtonType::Quit,
+                ButtonType::Seed => ButtonType::Play,
+                ButtonType::Controls => ButtonType::Seed,
+                ButtonType::Options => ButtonType::Controls,
+                ButtonType::Quit => ButtonType::Options,
+                _ => unreachable!("Tried to set button not in main menu!"),
+            }
+        }
+    }
+
+    // Pause Menu, navigation only runs in game while paused
+    if *app_state.get() == AppState::InGame {
+        if down {
+            selected_button.0 = match selected_button.0 {
+                ButtonType::Resume => ButtonType::Restart,
+                ButtonType::Restart => ButtonType::Options,
+                ButtonType::Options => ButtonType::MainMenu,
+                ButtonType::MainMenu => ButtonType::Resume,
+                _ => unreachable!("Tried to set button not in pause menu!"),
+            }
+        }
+        if up {
+            selected_button.0 = match selected_button.0 {
+                ButtonType::Resume => ButtonType::MainMenu,
+                ButtonType::Restart => ButtonType::Resume,
+                ButtonType::Options => ButtonType::Restart,
+                ButtonType::MainMenu => ButtonType::Options,
+                _ => unreachable!("Tried to set button not in pause menu!"),
+            }
+        }
     }
 
     // Game Over Screen
     if *app_state.get() == AppState::GameOver {
-        if keyboard_input.just_pressed(UP) || keyboard_input.just_pressed(DOWN) {
+        if up || down {
             selected_button.0 = match selected_button.0 {
                 ButtonType::Restart => ButtonType::MainMenu,
                 ButtonType::MainMenu => ButtonType::Restart,
@@ -393,6 +720,7 @@ pub fn set_default_button_selection(
     match *app_state.get() {
         AppState::MainMenu => selected_button.0 = ButtonType::Play,
         AppState::GameOver => selected_button.0 = ButtonType::Restart,
+        AppState::InGame => selected_button.0 = ButtonType::Resume,
         _ => unreachable!("Tried to set the default button to an invalid state!"),
     }
 }
@@ -410,35 +738,65 @@ pub fn update_button_colors(
     }
 }
 
+// Where a pressed menu button can take the player
+#[derive(SystemParam)]
+pub struct MenuTargets<'w> {
+    next_app_state: ResMut<'w, NextState<AppState>>,
+    next_options_state: ResMut<'w, NextState<OptionsState>>,
+    app_exit_events: EventWriter<'w, AppExit>,
+}
+
 pub fn handle_button_actions(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    selected_button: Res<SelectedButton>,
-    mut app_exit_events: EventWriter<AppExit>,
-    mut next_app_state: ResMut<NextState<AppState>>,
+    actions: ActionInput,
+    interaction_query: Query<(&ButtonType, &Interaction), Changed<Interaction>>,
+    mut selected_button: ResMut<SelectedButton>,
+    mut targets: MenuTargets,
     mut game_over_timer: ResMut<GameOverTimer>,
+    mut game_seed: ResMut<GameSeed>,
     time: Res<Time>,
 ) {
     game_over_timer.0.tick(time.delta());
-    if keyboard_input.just_pressed(SHOOT_KEY) {
+
+    // Tapping or clicking a button selects it and presses it
+    let tapped = interaction_query
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Pressed)
+        .map(|(button_type, _)| *button_type);
+    if let Some(button_type) = tapped {
+        selected_button.0 = button_type;
+    }
+
+    if actions.just_pressed(Action::Fire) || tapped.is_some() {
         match selected_button.0 {
             ButtonType::Play => {
                 // Start the game
-                next_app_state.set(AppState::InGame);
+                targets.next_app_state.set(AppState::InGame);
+            }
+            ButtonType::Seed => {
+                // Roll a new seed that can be shared
+                game_seed.0 = Some(rand::random());
+            }
+            ButtonType::Controls => {
+                targets.next_app_state.set(AppState::Controls);
+            }
+            ButtonType::Options => {
+                targets.next_options_state.set(OptionsState::Open);
             }
             ButtonType::Quit => {
                 // Quit the game
-                app_exit_events.send(AppExit);
+                targets.app_exit_events.send(AppExit);
             }
             ButtonType::MainMenu => {
-                next_app_state.set(AppState::MainMenu);
+                targets.next_app_state.set(AppState::MainMenu);
             }
             ButtonType::Restart => {
                 // Give the player some leeway to avoid unintended restarts
                 if !game_over_timer.0.finished() {
                     return;
                 }
-                next_app_state.set(AppState::InGame);
+                targets.next_app_state.set(AppState::InGame);
             }
+            ButtonType::Resume => unreachable!("Tried to press a pause menu button in a menu!"),
         }
     }
 }
@@ -451,8 +809,17 @@ pub fn spawn_game_over_ui(
     score: Res<Score>,
     level: Res<Level>,
     game_assets: Res<GameAssets>,
+    game_rng: Res<GameRng>,
+    high_scores: Res<HighScores>,
 ) {
-    build_game_over_ui(&mut commands, &score, &level, &game_assets);
+    // Ask for initials first if the score made it into the table
+    if high_scores.qualifies(score.0) {
+        let initials_entry = InitialsEntry::default();
+        build_initials_entry_ui(&mut commands, &score, &game_assets, &initials_entry);
+        commands.insert_resource(initials_entry);
+        return;
+    }
+    build_game_over_ui(&mut commands, &score, &level, &game_assets, &game_rng);
 }
 
 pub fn despawn_game_over_ui(
@@ -472,6 +839,7 @@ pub fn build_game_over_ui(
     score: &Res<Score>,
     level: &Res<Level>,
     game_assets: &Res<GameAssets>,
+    game_rng: &Res<GameRng>,
 ) {
     // Create the root node for the game over screen
     commands
@@ -578,6 +946,17 @@ pub fn build_game_over_ui(
                                 },
                                 ScoreText,
                             ));
+                            parent.spawn(TextBundle {
+                                text: Text::from_section(
+                                    format!("Seed: {}", game_rng.seed()),
+                                    TextStyle {
+                                        font: game_assets.font.clone(),
+                                        font_size: 12.0,
+                                        color: Color::GRAY,
+                                    },
+                                ),
+                                ..default()
+                            });
                         });
 
                     // Define the button node
@@ -672,7 +1051,7 @@ pub fn score_event(
     mut score_q: Query<(Entity, &mut FloatingScore)>,
     mut commands: Commands,
     time: Res<Time>,
-    window_q: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
 ) {
     // Tick existing, and despawn
     for (entity, mut score) in score_q.iter_mut() {
@@ -687,20 +1066,18 @@ pub fn score_event(
         return;
     }
 
-    let window = window_q.get_single().unwrap();
-
     // Create scores
     for event in score_er.read() {
         let mut x = 0.0;
         let mut y = 0.0;
-        if event.0.translation.x > window.width() - 21.0 {
+        if event.0.translation.x > playfield.width - 21.0 {
             x -= 20.0;
         }
         if event.0.translation.x < 21.0 {
             x += 13.0;
         }
 
-        if event.0.translation.y > window.height() - 20.0 {
+        if event.0.translation.y > playfield.height - 20.0 {
             y -= 20.0;
         }
         if event.0.translation.y < 10.0 {
@@ -758,3 +1135,782 @@ pub fn change_title_text_color(time: Res<Time>, mut query: Query<&mut Text, With
         text.sections[0].style.color = color;
     }
 }
+
+// Arcade style initials entry, shown on game over when the score is a high score
+#[derive(Resource)]
+pub struct InitialsEntry {
+    pub letters: [u8; INITIALS_LENGTH],
+    pub cursor: usize,
+}
+
+impl Default for InitialsEntry {
+    fn default() -> Self {
+        InitialsEntry {
+            letters: [b'A'; INITIALS_LENGTH],
+            cursor: 0,
+        }
+    }
+}
+
+impl InitialsEntry {
+    pub fn initials(&self) -> String {
+        String::from_utf8_lossy(&self.letters).to_string()
+    }
+}
+
+#[derive(Component)]
+pub struct InitialsEntryUI;
+
+#[derive(Component)]
+pub struct InitialsText;
+
+// One section per letter so the one being edited can be highlighted
+fn initials_sections(initials_entry: &InitialsEntry, font: &Handle<Font>) -> Vec<TextSection> {
+    initials_entry
+        .letters
+        .iter()
+        .enumerate()
+        .map(|(index, letter)| {
+            TextSection::new(
+                format!("{} ", *letter as char),
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    color: if index == initials_entry.cursor {
+                        INITIALS_CURSOR_COLOR
+                    } else {
+                        Color::WHITE
+                    },
+                },
+            )
+        })
+        .collect()
+}
+
+pub fn build_initials_entry_ui(
+    commands: &mut Commands,
+    score: &Res<Score>,
+    game_assets: &Res<GameAssets>,
+    initials_entry: &InitialsEntry,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::SpaceEvenly,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.95).into(),
+                ..default()
+            },
+            InitialsEntryUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "NEW HIGH SCORE",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 30.0,
+                    color: Color::YELLOW,
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("{:07}", score.0),
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 27.0,
+                    color: Color::WHITE,
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_sections(initials_sections(initials_entry, &game_assets.font)),
+                InitialsText,
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Up/Down to pick a letter, Shoot to enter it",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 12.0,
+                    color: Color::GRAY,
+                },
+            ));
+        });
+}
+
+// The initials being typed, the table they go into and the UI showing them
+#[derive(SystemParam)]
+pub struct InitialsEditor<'w, 's> {
+    entry: ResMut<'w, InitialsEntry>,
+    high_scores: ResMut<'w, HighScores>,
+    ui_query: Query<'w, 's, Entity, With<InitialsEntryUI>>,
+    text_query: Query<'w, 's, &'static mut Text, With<InitialsText>>,
+}
+
+// What the game over screen shows about the run
+#[derive(SystemParam)]
+pub struct RunSummary<'w> {
+    score: Res<'w, Score>,
+    level: Res<'w, Level>,
+    game_rng: Res<'w, GameRng>,
+}
+
+pub fn enter_initials(
+    mut commands: Commands,
+    actions: ActionInput,
+    touches: Res<Touches>,
+    mut editor: InitialsEditor,
+    mut game_over_timer: ResMut<GameOverTimer>,
+    run: RunSummary,
+    game_assets: Res<GameAssets>,
+) {
+    let initials_entry = &mut editor.entry;
+    let cursor = initials_entry.cursor;
+    if actions.just_pressed(Action::MoveUp) {
+        initials_entry.letters[cursor] = match initials_entry.letters[cursor] {
+            b'Z' => b'A',
+            letter => letter + 1,
+        };
+    }
+    if actions.just_pressed(Action::MoveDown) {
+        initials_entry.letters[cursor] = match initials_entry.letters[cursor] {
+            b'A' => b'Z',
+            letter => letter - 1,
+        };
+    }
+    if actions.just_pressed(Action::MoveLeft) {
+        initials_entry.cursor = cursor.saturating_sub(1);
+    }
+    // A tap takes the letter as it is, so touch players can always carry on
+    if actions.just_pressed(Action::Fire)
+        || actions.just_pressed(Action::MoveRight)
+        || touches.any_just_pressed()
+    {
+        initials_entry.cursor += 1;
+    }
+
+    // Last letter entered, save the score and move on to the game over screen
+    if initials_entry.cursor == INITIALS_LENGTH {
+        let initials = initials_entry.initials();
+        editor.high_scores.insert(initials, run.score.0);
+        editor.high_scores.save();
+
+        for entity in editor.ui_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands.remove_resource::<InitialsEntry>();
+        build_game_over_ui(
+            &mut commands,
+            &run.score,
+            &run.level,
+            &game_assets,
+            &run.game_rng,
+        );
+
+        // Give the player the same leeway before restarting as after dying
+        game_over_timer.0.reset();
+        return;
+    }
+
+    if editor.entry.is_changed() {
+        for mut text in editor.text_query.iter_mut() {
+            text.sections = initials_sections(&editor.entry, &game_assets.font);
+        }
+    }
+}
+
+// Controls Screen
+#[derive(Component)]
+pub struct ControlsMenu;
+
+// A line of the controls screen, the actions followed by the trackball
+// settings, reset and back
+#[derive(Component)]
+pub struct ControlsRow(pub usize);
+
+const TRACKBALL_ROW: usize = Action::ALL.len();
+const SENSITIVITY_ROW: usize = Action::ALL.len() + 1;
+const INERTIA_ROW: usize = Action::ALL.len() + 2;
+const RESET_ROW: usize = Action::ALL.len() + 3;
+const BACK_ROW: usize = Action::ALL.len() + 4;
+
+#[derive(Resource, Default)]
+pub struct ControlsSelection {
+    pub row: usize,
+    // Waiting for a key or button to bind to the selected action
+    pub listening: bool,
+}
+
+pub fn spawn_controls_menu(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.insert_resource(ControlsSelection::default());
+
+    let text = |value: &str, font_size: f32, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                color,
+                font_size,
+                font: game_assets.font.clone(),
+            },
+        )
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                ..default()
+            },
+            ControlsMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(text("Controls", 25.0, Color::GREEN));
+            parent.spawn(text(
+                "Press fire on an action, then a key or button to bind or unbind it",
+                8.0,
+                Color::GRAY,
+            ));
+            parent.spawn(text(
+                "Move left and right to change trackball settings",
+                8.0,
+                Color::GRAY,
+            ));
+            for row in 0..=BACK_ROW {
+                parent.spawn((text("", 12.0, Color::GREEN), ControlsRow(row)));
+            }
+        });
+}
+
+pub fn despawn_controls_menu(
+    mut commands: Commands,
+    controls_menu_query: Query<Entity, With<ControlsMenu>>,
+) {
+    for entity in controls_menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<ControlsSelection>();
+}
+
+pub fn navigate_controls(
+    actions: ActionInput,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut selection: ResMut<ControlsSelection>,
+    mut bindings: ResMut<Bindings>,
+    mut trackball_settings: ResMut<TrackballSettings>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if selection.listening {
+        let binding = keyboard_input
+            .get_just_pressed()
+            .next()
+            .map(|key| Binding::Key(*key))
+            .or_else(|| {
+                gamepad_buttons
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| Binding::Button(button.button_type))
+            });
+        if let Some(binding) = binding {
+            bindings.toggle(Action::ALL[selection.row], binding);
+            bindings.save();
+            selection.listening = false;
+        }
+        return;
+    }
+
+    if actions.just_pressed(Action::MoveDown) {
+        selection.row = (selection.row + 1) % (BACK_ROW + 1);
+    }
+    if actions.just_pressed(Action::MoveUp) {
+        selection.row = (selection.row + BACK_ROW) % (BACK_ROW + 1);
+    }
+    if actions.just_pressed(Action::Menu) {
+        next_app_state.set(AppState::MainMenu);
+    }
+
+    let mut steps = 0.0;
+    if actions.just_pressed(Action::MoveLeft) {
+        steps -= 1.0;
+    }
+    if actions.just_pressed(Action::MoveRight) {
+        steps += 1.0;
+    }
+    if steps != 0.0 && selection.row == SENSITIVITY_ROW {
+        trackball_settings.adjust_sensitivity(steps);
+        trackball_settings.save();
+    }
+    if steps != 0.0 && selection.row == INERTIA_ROW {
+        trackball_settings.adjust_inertia(steps);
+        trackball_settings.save();
+    }
+
+    if actions.just_pressed(Action::Fire) {
+        match selection.row {
+            TRACKBALL_ROW => {
+                trackball_settings.enabled = !trackball_settings.enabled;
+                trackball_settings.save();
+            }
+            SENSITIVITY_ROW | INERTIA_ROW => {}
+            RESET_ROW => {
+                *bindings = Bindings::default();
+                bindings.save();
+                *trackball_settings = TrackballSettings::default();
+                trackball_settings.save();
+            }
+            BACK_ROW => next_app_state.set(AppState::MainMenu),
+            _ => selection.listening = true,
+        }
+    }
+}
+
+pub fn update_controls_text(
+    selection: Res<ControlsSelection>,
+    bindings: Res<Bindings>,
+    trackball_settings: Res<TrackballSettings>,
+    mut row_query: Query<(&ControlsRow, &mut Text, &mut BackgroundColor)>,
+) {
+    if !selection.is_changed() && !bindings.is_changed() && !trackball_settings.is_changed() {
+        return;
+    }
+    for (row, mut text, mut background_color) in row_query.iter_mut() {
+        let selected = row.0 == selection.row;
+        text.sections[0].value = match row.0 {
+            TRACKBALL_ROW if trackball_settings.enabled => "Trackball: On".to_string(),
+            TRACKBALL_ROW => "Trackball: Off".to_string(),
+            SENSITIVITY_ROW => format!("Sensitivity: {:.1}", trackball_settings.sensitivity),
+            INERTIA_ROW => format!("Inertia: {:.2}", trackball_settings.inertia),
+            RESET_ROW => "Reset defaults".to_string(),
+            BACK_ROW => "Back".to_string(),
+            _ if selected && selection.listening => "Press a key or button...".to_string(),
+            index => {
+                let action = Action::ALL[index];
+                let bound: Vec<String> = bindings
+                    .get(action)
+                    .iter()
+                    .map(|binding| binding.label())
+                    .collect();
+                format!("{}: {}", action.label(), bound.join(", "))
+            }
+        };
+        *background_color = if selected {
+            BUTTON_HOVER_COLOR.into()
+        } else {
+            BUTTON_NORMAL_COLOR.into()
+        };
+    }
+}
+
+// Pause Menu
+#[derive(Component)]
+pub struct PauseMenu;
+
+pub fn spawn_pause_menu(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                ..default()
+            },
+            PauseMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "PAUSED",
+                    TextStyle {
+                        font: game_assets.font.clone(),
+                        font_size: 40.0,
+                        color: Color::GREEN,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(40.0)),
+                    ..default()
+                }),
+            );
+
+            let buttons = [
+                (ButtonType::Resume, "Resume"),
+                (ButtonType::Restart, "Restart"),
+                (ButtonType::Options, "Options"),
+                (ButtonType::MainMenu, "Quit to Menu"),
+            ];
+            for (button_type, label) in buttons {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(240.0),
+                                height: Val::Px(60.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: BUTTON_NORMAL_COLOR.into(),
+                            ..default()
+                        },
+                        button_type,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: game_assets.font.clone(),
+                                font_size: 20.0,
+                                color: Color::GREEN,
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn despawn_pause_menu(
+    mut commands: Commands,
+    pause_menu_query: Query<Entity, With<PauseMenu>>,
+) {
+    for entity in pause_menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn handle_pause_menu_actions(
+    actions: ActionInput,
+    interaction_query: Query<(&ButtonType, &Interaction), Changed<Interaction>>,
+    mut selected_button: ResMut<SelectedButton>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_options_state: ResMut<NextState<OptionsState>>,
+) {
+    // Tapping or clicking a button selects it and presses it
+    let tapped = interaction_query
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Pressed)
+        .map(|(button_type, _)| *button_type);
+    if let Some(button_type) = tapped {
+        selected_button.0 = button_type;
+    }
+
+    if !actions.just_pressed(Action::Fire) && tapped.is_none() {
+        return;
+    }
+    match selected_button.0 {
+        ButtonType::Resume => {}
+        ButtonType::Restart => next_app_state.set(AppState::Restarting),
+        ButtonType::Options => {
+            // Stay paused underneath the options
+            next_options_state.set(OptionsState::Open);
+            return;
+        }
+        ButtonType::MainMenu => next_app_state.set(AppState::MainMenu),
+        _ => unreachable!("Tried to press a button not in the pause menu!"),
+    }
+    next_game_state.set(GameState::Running);
+}
+
+// Pause when the player switches to another tab or window
+pub fn pause_on_focus_loss(
+    mut focus_events: EventReader<WindowFocused>,
+    app_state: Res<State<AppState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let lost_focus = focus_events.read().any(|event| !event.focused);
+    if lost_focus && *app_state.get() == AppState::InGame {
+        next_game_state.set(GameState::Paused);
+    }
+}
+
+// Options Screen
+#[derive(Component)]
+pub struct OptionsMenu;
+
+#[derive(Component)]
+pub struct OptionsRow(pub usize);
+
+const MASTER_VOLUME_ROW: usize = 0;
+const MUSIC_VOLUME_ROW: usize = 1;
+const SFX_VOLUME_ROW: usize = 2;
+const MUTE_ROW: usize = 3;
+const OPTIONS_BACK_ROW: usize = 4;
+
+#[derive(Resource, Default)]
+pub struct OptionsSelection(pub usize);
+
+pub fn spawn_options_menu(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.insert_resource(OptionsSelection::default());
+
+    let text = |value: &str, font_size: f32, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                color,
+                font_size,
+                font: game_assets.font.clone(),
+            },
+        )
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                // Draw over the main menu or pause menu it was opened from, and
+                // keep taps from reaching their buttons
+                z_index: ZIndex::Global(1),
+                focus_policy: FocusPolicy::Block,
+                ..default()
+            },
+            OptionsMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(text("Options", 25.0, Color::GREEN));
+            parent.spawn(text(
+                "Move left and right to change a volume",
+                8.0,
+                Color::GRAY,
+            ));
+            for row in 0..=OPTIONS_BACK_ROW {
+                parent.spawn((
+                    text("", 15.0, Color::GREEN),
+                    OptionsRow(row),
+                    Interaction::default(),
+                ));
+            }
+        });
+}
+
+pub fn despawn_options_menu(
+    mut commands: Commands,
+    options_menu_query: Query<Entity, With<OptionsMenu>>,
+) {
+    for entity in options_menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<OptionsSelection>();
+}
+
+pub fn navigate_options(
+    actions: ActionInput,
+    interaction_query: Query<(&OptionsRow, &Interaction), Changed<Interaction>>,
+    mut selection: ResMut<OptionsSelection>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut next_options_state: ResMut<NextState<OptionsState>>,
+) {
+    if actions.just_pressed(Action::MoveDown) {
+        selection.0 = (selection.0 + 1) % (OPTIONS_BACK_ROW + 1);
+    }
+    if actions.just_pressed(Action::MoveUp) {
+        selection.0 = (selection.0 + OPTIONS_BACK_ROW) % (OPTIONS_BACK_ROW + 1);
+    }
+    if actions.just_pressed(Action::Menu) {
+        next_options_state.set(OptionsState::Closed);
+    }
+
+    let mut steps = 0.0;
+    if actions.just_pressed(Action::MoveLeft) {
+        steps -= 1.0;
+    }
+    if actions.just_pressed(Action::MoveRight) {
+        steps += 1.0;
+    }
+    let mut pressed = actions.just_pressed(Action::Fire);
+
+    // A tap selects and presses a row, and turns a volume up a step, wrapping
+    // back round to silent
+    let tapped = interaction_query
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Pressed)
+        .map(|(row, _)| row.0);
+    if let Some(row) = tapped {
+        selection.0 = row;
+        pressed = true;
+    }
+
+    let settings = *audio_settings;
+    match selection.0 {
+        MASTER_VOLUME_ROW | MUSIC_VOLUME_ROW | SFX_VOLUME_ROW => {
+            let level = match selection.0 {
+                MASTER_VOLUME_ROW => &mut audio_settings.master,
+                MUSIC_VOLUME_ROW => &mut audio_settings.music,
+                _ => &mut audio_settings.sfx,
+            };
+            if steps != 0.0 {
+                *level = AudioSettings::step(*level, steps);
+            } else if tapped.is_some() {
+                *level = if *level >= 1.0 {
+                    0.0
+                } else {
+                    AudioSettings::step(*level, 1.0)
+                };
+            }
+        }
+        MUTE_ROW if pressed => audio_settings.muted = !audio_settings.muted,
+        OPTIONS_BACK_ROW if pressed => next_options_state.set(OptionsState::Closed),
+        _ => {}
+    }
+
+    if *audio_settings != settings {
+        audio_settings.save();
+    }
+}
+
+pub fn update_options_text(
+    selection: Res<OptionsSelection>,
+    audio_settings: Res<AudioSettings>,
+    mut row_query: Query<(&OptionsRow, &mut Text, &mut BackgroundColor)>,
+) {
+    if !selection.is_changed() && !audio_settings.is_changed() {
+        return;
+    }
+    let percent = |level: f64| format!("{:.0}%", level * 100.0);
+    for (row, mut text, mut background_color) in row_query.iter_mut() {
+        text.sections[0].value = match row.0 {
+            MASTER_VOLUME_ROW => format!("Master: {}", percent(audio_settings.master)),
+            MUSIC_VOLUME_ROW => format!("Music: {}", percent(audio_settings.music)),
+            SFX_VOLUME_ROW => format!("Sound effects: {}", percent(audio_settings.sfx)),
+            MUTE_ROW if audio_settings.muted => "Mute: On".to_string(),
+            MUTE_ROW => "Mute: Off".to_string(),
+            _ => "Back".to_string(),
+        };
+        *background_color = if row.0 == selection.0 {
+            BUTTON_HOVER_COLOR.into()
+        } else {
+            BUTTON_NORMAL_COLOR.into()
+        };
+    }
+}
+
+// Attract Mode
+#[derive(Component)]
+pub struct DemoUi;
+
+pub fn build_demo_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            DemoUi,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Millipede!",
+                    TextStyle {
+                        font: game_assets.font.clone(),
+                        font_size: 30.0,
+                        color: Color::GREEN,
+                    },
+                ),
+                TitleText,
+            ));
+            parent.spawn(TextBundle::from_section(
+                "DEMO - PRESS ANY KEY",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 15.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+pub fn despawn_demo_ui(mut commands: Commands, demo_ui_query: Query<Entity, With<DemoUi>>) {
+    for entity in demo_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Swarm Tally
+#[derive(Component)]
+pub struct SwarmTallyUi;
+
+pub fn spawn_swarm_tally_ui(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    swarm_wave: Res<SwarmWave>,
+) {
+    let text_style = |font_size, color| TextStyle {
+        font: game_assets.font.clone(),
+        font_size,
+        color,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            },
+            SwarmTallyUi,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "SWARM BONUS",
+                text_style(30.0, Color::YELLOW),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("BEES {}/{}", swarm_wave.kills, swarm_wave.bees),
+                text_style(TEXT_SIZE, Color::WHITE),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("POINTS {}", swarm_wave.points),
+                text_style(TEXT_SIZE, Color::WHITE),
+            ));
+        });
+}
+
+pub fn despawn_swarm_tally_ui(
+    mut commands: Commands,
+    swarm_tally_ui_query: Query<Entity, With<SwarmTallyUi>>,
+) {
+    for entity in swarm_tally_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}